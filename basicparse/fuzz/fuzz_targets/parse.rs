@@ -0,0 +1,10 @@
+#![no_main]
+
+use basicparse::{Parser, Tokenizer};
+use libfuzzer_sys::fuzz_target;
+
+// whatever the input, tokenizing and parsing it has to end in tokens or errors, never a panic
+fuzz_target!(|src: &str| {
+	for _ in Tokenizer::new(src) {}
+	let _ = Parser::new(src).recover();
+});
@@ -1,6 +1,4 @@
-use std::num::{ParseFloatError, ParseIntError};
-
-use langlib::Expr;
+use langlib::Span;
 
 use crate::Token;
 
@@ -10,9 +8,28 @@ pub enum Error {
 	Read(#[from] readuntil_ext::Error),
 	#[error("{context}:\n{err}")]
 	Context { context: String, err: Box<Self> },
+	#[error("at {span}: {err}")]
+	At { span: Span, err: Box<Self> },
 
 	#[error("tokenizer tokenized everything in its reader. this error is hidden most of the time")]
 	TokenizerFinished,
+	#[error("couldn't find matching closing bracket '{0}'")]
+	UnclosedBracket(char),
+	#[error("expected closing bracket '{expected}', got '{got}'")]
+	MismatchedClosingBracket { expected: char, got: char },
+	#[error("unexpected closing bracket '{0}'")]
+	UnexpectedClosingBracket(char),
+	#[error("block comment is never closed (missing `*/`)")]
+	UnclosedComment,
+	#[error("invalid character '{0}'")]
+	InvalidChar(char),
+	#[error("invalid escape sequence `{0}` in string literal")]
+	InvalidEscape(String),
+	#[error("invalid loop label `{0}`, labels look like `'name`")]
+	InvalidLabel(String),
+
+	#[error("code is nested too deeply (more than {0} levels)")]
+	TooDeep(usize),
 
 	#[error("unexpected end of input while reading expr")]
 	EOFExpr,
@@ -20,10 +37,12 @@ pub enum Error {
 	EOFStatement,
 	#[error("unexpected end of input while reading reach (value or named variable)")]
 	EOFReach,
-	#[error("expected name of variable after let")]
+	#[error("expected name of variable (or a pattern) after let or const")]
 	ExpectedVariableName,
 	#[error("expected eq sign after name of variable in variable declaration")]
 	ExpectedEqLet,
+	#[error("only variables and their fields and elements (like `a.b.[i]`) can be assigned to")]
+	InvalidAssignTarget,
 	#[error("expected eq sign after ident name in statement")]
 	ExpectedEqIdent,
 	#[error("expected something after ident in statement")]
@@ -39,24 +58,62 @@ pub enum Error {
 	ExpectedInFor(Option<Token>),
 	#[error("expected iterator after `in` keyword in for loop, got: {0:?}")]
 	ExpectedIterFor(Option<Token>),
+	#[error("expected curly braces with the arms of the match expression after the value")]
+	ExpectedMatchArms,
+	#[error("expected `=>` after pattern in match arm, got: {0:?}")]
+	ExpectedFatArrow(Option<Token>),
+	#[error("unexpected end of input while reading pattern")]
+	EOFPattern,
+	#[error(
+		"expected a pattern (`_`, a name, a literal, `None`, `[...]` or `obj {{...}}`), got: {0:?}"
+	)]
+	InvalidPattern(Token),
+	#[error("an array pattern can only have one `..`")]
+	MultipleRests,
+	#[error("expected a loop after label '{0}:, got: {1:?}")]
+	ExpectedLoopAfterLabel(String, Option<Token>),
+	#[error(
+		"expected the path of the file to import (a string literal), or `{{ names }}` before it, got: {0:?}"
+	)]
+	ExpectedImportPath(Option<Token>),
+	#[error("expected `{0}` in import statement, got: {1:?}")]
+	ExpectedImportKeyword(&'static str, Option<Token>),
+	#[error("expected a name in import statement, got: {0:?}")]
+	ExpectedImportName(Option<Token>),
+	#[error("`{0}` is imported more than once")]
+	DuplicateImport(String),
+	#[error("`{0}` can only be used inside of a loop")]
+	OutsideLoop(&'static str),
+	#[error("there's no loop labeled '{0} around this")]
+	UnknownLabel(String),
 
-	#[error("invalid number literal, couldn't parse as i32 or f32")]
-	InvalidNumLit {
-		i32err: ParseIntError,
-		f32err: ParseFloatError,
-	},
+	#[error("invalid number literal `{0}`")]
+	InvalidNumLit(String),
+	#[error("integer literal `{0}` doesn't fit in an i32")]
+	IntLitOverflow(String),
 	#[error("invalid first token while reading a reach: {0:?}")]
 	InvalidFirstReach(Token),
+	#[error("expected a value, got a block. object literals look like `obj {{ key: value }}`")]
+	UnexpectedBlock,
 	#[error("expected parens at function declaration")]
 	ExpectedFnDeclParens,
+	#[error("expected a `|` after the parameters of a lambda")]
+	ExpectedLambdaPipe,
 	#[error("expected block/opening curly braces")]
 	ExpectedBlock,
 
-	#[error("unsuccessful expansion of expr (this error is usually handled internally)")]
-	ExprExpand(Expr),
-
 	#[error("expected ident in function arguments, got {0:?}")]
 	ExpectedIdentGot(Token),
+	#[error("expected comma between items, got {0:?}")]
+	ExpectedCommaGot(Token),
+	#[error("function has more than one argument called `{0}`")]
+	DuplicateArg(String),
+	#[error("`return` can only be used inside of a function")]
+	ReturnOutsideFn,
+	#[error("argument `{0}` is passed by name more than once")]
+	DuplicateNamedArg(String),
+	#[error("positional arguments have to come before the ones passed by name")]
+	PositionalAfterNamed,
 	#[error(
 		"invalid index: expected either an ident or an i32 literal on right hand side of . access operation"
 	)]
@@ -69,6 +126,33 @@ impl Error {
 			err: Box::new(self),
 		}
 	}
+	/// attaches the given location to the error, unless it already knows a more precise one \
+	/// errors used internally as signals are left as-is
+	pub fn at(self, span: Span) -> Self {
+		match self {
+			Self::TokenizerFinished => self,
+			err if err.span().is_some() => err,
+			err => Self::At {
+				span,
+				err: Box::new(err),
+			},
+		}
+	}
+	/// the location in the source code where this error happened, if known
+	pub fn span(&self) -> Option<Span> {
+		match self {
+			Self::At { span, .. } => Some(*span),
+			Self::Context { err, .. } => err.span(),
+			_ => None,
+		}
+	}
+	/// the error itself, without any context or location around it
+	pub fn root(&self) -> &Error {
+		match self {
+			Self::At { err, .. } | Self::Context { err, .. } => err.root(),
+			err => err,
+		}
+	}
 }
 
 pub type Result<T, E = Error> = core::result::Result<T, E>;
@@ -1,90 +1,220 @@
-use std::iter::Peekable;
+use std::{cell::RefCell, iter::Peekable, num::IntErrorKind, rc::Rc};
 
 use crate::*;
 
-use langlib::{Block, Expr, Function, Index, IntoIndex, Reach, Statement, Value};
+use langlib::{
+	ArithOp, Block, Expr, Function, Imports, Index, IntoIndex, MatchArm, Param, Pattern, Reach,
+	Span, Statement, Value,
+};
 
 #[derive(Clone, Debug)]
-pub struct Parser<I: Iterator<Item = Result<Token>>> {
+pub struct Parser<I: Iterator<Item = Result<SpannedToken>>> {
 	iter: Peekable<I>,
+	/// span of the last token we consumed
+	last_span: Span,
+	/// the labels of the loops we're inside of (in the current function), innermost last \
+	/// used to make sure `break` and `continue` have a loop to break out of
+	loops: Vec<Option<String>>,
+	/// true if we're inside the body of a function, where `return` is allowed
+	in_fn: bool,
+	/// how deeply nested the code we're reading is, see [Parser::nested]
+	depth: usize,
+	/// how many tokens we've consumed so far, so [Parser::recover] can tell if a statement that
+	/// failed to parse got anywhere
+	taken: usize,
+	/// where errors go when we keep going after them (see [Parser::recover]) \
+	/// shared with the parsers reading the inside of brackets. [None] if errors stop the parser
+	errors: Option<Rc<RefCell<Vec<Error>>>>,
 }
+/// how many blocks, expressions and patterns deep the code can go
+const MAX_DEPTH: usize = 64;
+
+/// what [Parser]s reading the inside of brackets read from
+type Inner =
+	std::iter::Map<std::vec::IntoIter<SpannedToken>, fn(SpannedToken) -> Result<SpannedToken>>;
+
 impl<'a> Parser<Tokenizer<'a>> {
 	pub fn new(src: &'a str) -> Self {
 		Self {
 			iter: Tokenizer::new(src).peekable(),
+			last_span: Span::default(),
+			loops: Vec::new(),
+			in_fn: false,
+			depth: 0,
+			taken: 0,
+			errors: None,
 		}
 	}
 }
-impl<I: Iterator<Item = Result<Token>> + Clone> Parser<I> {
+impl<I: Iterator<Item = Result<SpannedToken>> + Clone> Parser<I> {
 	pub fn from_iter(iter: impl IntoIterator<IntoIter = I>) -> Self {
 		Self {
 			iter: iter.into_iter().peekable(),
+			last_span: Span::default(),
+			loops: Vec::new(),
+			in_fn: false,
+			depth: 0,
+			taken: 0,
+			errors: None,
+		}
+	}
+	/// a parser for the tokens inside the brackets we just read, that knows which loops (and
+	/// whether a function) it's in
+	fn inner(&self, tokens: Vec<SpannedToken>) -> Parser<Inner> {
+		Parser {
+			iter: tokens.into_iter().map(Ok as fn(_) -> _).peekable(),
+			last_span: self.last_span,
+			loops: self.loops.clone(),
+			in_fn: self.in_fn,
+			depth: self.depth,
+			taken: 0,
+			errors: self.errors.clone(),
+		}
+	}
+	/// use for parsers reading the inside of brackets, so errors at the very beginning
+	/// (or in empty brackets) still point somewhere useful
+	pub fn starting_at(self, span: Span) -> Self {
+		Self {
+			last_span: span,
+			..self
+		}
+	}
+
+	fn next(&mut self) -> Option<Result<Token>> {
+		let next = self.iter.next()?;
+		self.taken += 1;
+		Some(next.map(|SpannedToken { token, span }| {
+			self.last_span = span;
+			token
+		}))
+	}
+	/// runs `f` one level deeper, or errors if the code is nested too deeply to read without
+	/// running out of stack
+	fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+		if self.depth >= MAX_DEPTH {
+			return Err(Error::TooDeep(MAX_DEPTH).at(self.peek_span()));
+		}
+		self.depth += 1;
+		let out = f(self);
+		self.depth -= 1;
+		out
+	}
+	/// consumes the next token if `f` is true for it
+	fn next_if(&mut self, f: impl FnOnce(&Token) -> bool) -> Option<Token> {
+		let next = self
+			.iter
+			.next_if(|next| next.as_ref().is_ok_and(|next| f(&next.token)))?;
+		let SpannedToken { token, span } = next.ok()?;
+		self.taken += 1;
+		self.last_span = span;
+		Some(token)
+	}
+	fn peek(&mut self) -> Option<Result<&Token, &Error>> {
+		self.iter.peek().map(|next| next.as_ref().map(|t| &t.token))
+	}
+	/// span of the next token, or the last one if there's nothing left
+	fn peek_span(&mut self) -> Span {
+		match self.iter.peek() {
+			Some(Ok(next)) => next.span,
+			_ => self.last_span,
 		}
 	}
 
 	pub fn read_reach(&mut self) -> Result<Reach> {
-		let a = self.iter.next().ok_or(Error::EOFReach)??;
+		let a = self.next().ok_or(Error::EOFReach)??;
+		let span = self.last_span;
+		self.read_reach_from(a).map_err(|err| err.at(span))
+	}
+	fn read_reach_from(&mut self, a: Token) -> Result<Reach> {
 		match a {
 			Token::Ident(name) => {
-				if name == "obj" {
-					if let Some(Ok(Token::Curly(_))) = self.iter.peek() {
-						if let Some(Ok(Token::Curly(map))) = self.iter.next() {
-							// object literal
-							let parser = Parser::from_iter(map.into_iter().map(Ok)).object();
-							return Ok(Reach::ObjectLiteral(
-								parser.collect::<Result<Vec<_>, _>>()?,
-							));
-						} else {
-							panic!("peeked != iter.next()");
-						}
-					}
+				if name == "obj"
+					&& let Some(Token::Curly(map)) = self.next_if(|t| matches!(t, Token::Curly(_)))
+				{
+					// object literal
+					let parser = self.inner(map).object();
+					return Ok(Reach::ObjectLiteral(parser.collect::<Result<Vec<_>, _>>()?));
+				}
+
+				if name == "None" {
+					return Ok(Reach::Value(Value::None));
 				}
 
 				// regular variable reference
 				Ok(Reach::Named(name))
 			}
 			Token::StrLit(s) => Ok(Reach::Value(Value::String(s))),
-			Token::NumLit(s) => match s.parse() {
-				Ok(a) => Ok(Reach::Value(Value::i32(a))),
-				Err(i32err) => match s.parse() {
-					Ok(a) => Ok(Reach::Value(Value::f32(a))),
-					Err(f32err) => return Err(Error::InvalidNumLit { f32err, i32err }),
-				},
-			},
+			Token::FmtStr(parts) => {
+				let mut exprs = Vec::with_capacity(parts.len());
+				for part in parts {
+					let expr = match part {
+						StrPart::Lit(s) => Expr::Reach(Reach::Value(Value::String(s))),
+						StrPart::Expr(span, tokens) => {
+							let mut parser = self.inner(tokens).starting_at(span);
+							parser.read_expr().with_context(|| {
+								"while reading an expression inside a format string".into()
+							})?
+						}
+					};
+					exprs.push(expr);
+				}
+				Ok(Reach::Expr(Box::new(Expr::Format(exprs))))
+			}
+			Token::NumLit(s) => Ok(Reach::Value(parse_num(&s)?)),
+			// prefix operators take everything up to the next binary operator, so `-a.b` is `-(a.b)`
+			// and `!f(x)` is `!(f(x))`, but `-a ** 2` is `(-a) ** 2`
+			Token::Minus => {
+				if let Some(Token::NumLit(s)) = self.next_if(|t| matches!(t, Token::NumLit(_))) {
+					// negative number literal
+					return Ok(Reach::Value(parse_num(&format!("-{s}"))?));
+				}
+				let a = self
+					.nested(Self::read_postfix)
+					.with_context(|| "while reading negated value".into())?;
+				Ok(Expr::Neg(a.into_reach()).into_reach())
+			}
+			Token::Not => {
+				let a = self
+					.nested(Self::read_postfix)
+					.with_context(|| "while reading value after !".into())?;
+				Ok(Expr::Not(a.into_reach()).into_reach())
+			}
 			Token::Brackets(b) => {
 				// array literal
-				let parser = Parser::from_iter(b.into_iter().map(Ok)).comma_separated_expressions();
+				let parser = self.inner(b).comma_separated_expressions();
 				let elements = parser.collect::<Result<Vec<_>, _>>().with_context(|| {
 					format!("while parsing expressions inside an array literal")
 				})?;
 				Ok(Reach::ArrayLiteral(elements))
 			}
 			Token::Fn => {
-				let parens = self.iter.next().ok_or(Error::ExpectedFnDeclParens)??;
+				let parens = self.next().ok_or(Error::ExpectedFnDeclParens)??;
 				if let Token::Parens(parens) = parens {
-					let mut arg_names = parens.into_iter().map(|token| match token {
-						Token::Ident(name) => Ok(name),
-						token => Err(Error::ExpectedIdentGot(token)),
-					});
-					let arg_name = arg_names.next();
-					let arg_name = if let Some(arg_name) = arg_name {
-						Some(arg_name?)
-					} else {
-						None
-					};
-					assert!(
-						arg_names.next().is_none(),
-						"only one argument per function for now"
-					);
-
-					let block = self.read_block()?;
-					Ok(Reach::Value(Value::Function(Function { arg_name, block })))
+					let params = self.inner(parens).read_params()?;
+					let block = self.function_body(Self::read_block)?;
+					Ok(Reach::Value(Value::Function(Function { params, block })))
 				} else {
 					Err(Error::ExpectedFnDeclParens)
 				}
 			}
+			// short lambda, `|a, b| a + b` is the same as `fn(a, b) { a + b }`
+			Token::Pipe => {
+				let mut params = Vec::new();
+				loop {
+					match self.next().ok_or(Error::ExpectedLambdaPipe)?? {
+						Token::Pipe => break,
+						token => params.push(SpannedToken {
+							token,
+							span: self.last_span,
+						}),
+					}
+				}
+				let params = self.inner(params).read_params()?;
+				self.read_lambda(params)
+			}
+			Token::Or => self.read_lambda(Vec::new()),
 			Token::Parens(parens) => {
-				let mut parser = Parser::from_iter(parens.into_iter().map(Ok));
+				let mut parser = self.inner(parens);
 				let expr = parser.read_expr().with_context(|| {
 					format!(
 						"while reading inside parentheses (reading a reach that's an expr in disguise)"
@@ -92,262 +222,722 @@ impl<I: Iterator<Item = Result<Token>> + Clone> Parser<I> {
 				})?;
 				Ok(Reach::Expr(Box::new(expr)))
 			}
-			_ => unimplemented!("{a:?} as reach"),
+			Token::Curly(_) => Err(Error::UnexpectedBlock),
+			a => Err(Error::InvalidFirstReach(a)),
 		}
 	}
-	fn expand_expr_internal(&mut self, expr: Expr) -> Result<Expr> {
-		let a = match self.iter.peek() {
-			Some(Ok(Token::Plus)) => {
-				self.iter.next();
-				let reach = expr.into_reach();
-
-				let b = self.read_expr()?.into_reach();
-
-				Expr::Add(reach, b)
-			}
-			Some(Ok(Token::Minus)) => {
-				self.iter.next();
+	fn read_if(&mut self) -> Result<Expr> {
+		self.next();
+		let cond = self
+			.read_expr()
+			.with_context(|| format!("while reading condition in if statement"))?;
 
-				let reach = expr.into_reach();
-				let b = self.read_expr()?.into_reach();
+		let if_true = self
+			.read_block()
+			.with_context(|| format!("while reading if true branch in if statement"))?;
 
-				Expr::Sub(reach, b)
+		let if_false = match self.peek() {
+			Some(Ok(Token::Else)) => {
+				self.next();
+				if let Some(Ok(Token::If)) = self.peek() {
+					// `else if` is just an if inside the else branch
+					let start = self.peek_span();
+					let if_false = self
+						.nested(Self::read_if)
+						.with_context(|| "while reading else if branch in if statement".into())?;
+					return Ok(Expr::Conditional {
+						condition: Reach::Expr(Box::new(cond)),
+						if_true: Reach::Expr(Box::new(Expr::Block(if_true))),
+						if_false: if_false.spanned(start.to(self.last_span)).into_reach(),
+					});
+				}
+				let if_false = self
+					.read_block()
+					.with_context(|| format!("while reading else branch in if statement"))?;
+				Reach::Expr(Box::new(Expr::Block(if_false)))
 			}
-			Some(Ok(Token::Dot)) => {
-				self.iter.next();
-
-				let reach = expr.into_reach();
-				let b = self.read_reach().with_context(|| {
-					format!("while reading right-hand side of . indexing access")
-				})?;
+			_ => Reach::Value(Value::None),
+		};
 
-				let index = match b {
-					Reach::Named(name) => IntoIndex::Index(Index::Ident(name)),
-					Reach::Value(Value::i32(i)) => IntoIndex::Index(Index::NumLit(i)),
-					Reach::ArrayLiteral(arr) if arr.len() == 1 => IntoIndex::Expr(Box::new(
-						arr.into_iter()
-							.next()
-							.expect("we just checked there's at least one element"),
-					)),
-					_ => return Err(Error::InvalidIndex),
-				};
+		Ok(Expr::Conditional {
+			condition: Reach::Expr(Box::new(cond)),
+			if_true: Reach::Expr(Box::new(Expr::Block(if_true))),
+			if_false,
+		})
+	}
+	fn read_match(&mut self) -> Result<Expr> {
+		self.next();
+		let value = self
+			.read_expr()
+			.with_context(|| "while reading the value to match on".into())?;
 
-				Expr::Index(reach, index)
+		let arms = match self.next().ok_or(Error::ExpectedMatchArms)?? {
+			Token::Curly(arms) => {
+				let span = self.last_span;
+				self.inner(arms).starting_at(span).read_arms()?
 			}
-			Some(Ok(Token::Eq)) => {
-				let mut clone = self.clone();
-				clone.iter.next();
-				if let Some(Ok(Token::Eq)) = clone.iter.peek() {
-					self.iter.next();
-					self.iter.next();
+			_ => return Err(Error::ExpectedMatchArms),
+		};
+		Ok(Expr::Match {
+			value: value.into_reach(),
+			arms,
+		})
+	}
+	/// reads the arms of a match expression (the inside of `match a { ... }`)
+	fn read_arms(&mut self) -> Result<Vec<MatchArm>> {
+		let mut arms = Vec::new();
+		while self.peek().is_some() {
+			let i = arms.len();
+			let pattern = self
+				.read_pattern()
+				.with_context(|| format!("while reading the pattern of match arm {i}"))?;
 
-					let b = self
+			let guard = match self.peek() {
+				Some(Ok(Token::If)) => {
+					self.next();
+					let guard = self
 						.read_expr()
-						.with_context(|| format!("while reading right side of equality check"))?;
-					Expr::Cmp(expr.into_reach(), b.into_reach())
-				} else {
-					return Err(Error::ExprExpand(expr)); // base case from outer match
+						.with_context(|| format!("while reading the guard of match arm {i}"))?;
+					Some(guard)
 				}
-			}
-			Some(Ok(Token::Gt)) => {
-				self.iter.next();
-
-				let b = self
-					.read_expr()
-					.with_context(|| format!("while reading right side of greater than check"))?;
+				_ => None,
+			};
 
-				Expr::Gt(expr.into_reach(), b.into_reach())
+			match self.next() {
+				Some(Ok(Token::FatArrow)) => {}
+				Some(Err(err)) => return Err(err),
+				other => {
+					return Err(
+						Error::ExpectedFatArrow(other.and_then(Result::ok)).at(self.last_span)
+					);
+				}
 			}
-			Some(Ok(Token::Lt)) => {
-				self.iter.next();
 
-				let b = self
-					.read_expr()
-					.with_context(|| format!("while reading right side of less than check"))?;
+			let start = self.peek_span();
+			let body = match self.peek() {
+				Some(Ok(Token::Curly(_))) => {
+					let block = self
+						.read_block()
+						.with_context(|| format!("while reading the body of match arm {i}"))?;
+					// just like in rust, there doesn't have to be a comma after a block
+					if let Some(Ok(Token::Comma)) = self.peek() {
+						self.next();
+					}
+					Expr::Block(block).spanned(start.to(self.last_span))
+				}
+				_ => {
+					let body = self
+						.read_expr()
+						.with_context(|| format!("while reading the body of match arm {i}"))?;
+					self.read_comma()?;
+					body
+				}
+			};
 
-				Expr::Lt(expr.into_reach(), b.into_reach())
+			arms.push(MatchArm {
+				pattern,
+				guard,
+				body,
+			});
+		}
+		Ok(arms)
+	}
+	/// reads a pattern, like the left side of a match arm
+	fn read_pattern(&mut self) -> Result<Pattern> {
+		let token = self.next().ok_or(Error::EOFPattern)??;
+		let span = self.last_span;
+		self.nested(|parser| parser.read_pattern_from(token))
+			.map_err(|err| err.at(span))
+	}
+	fn read_pattern_from(&mut self, token: Token) -> Result<Pattern> {
+		match token {
+			Token::Ident(name) if name == "_" => Ok(Pattern::Wildcard),
+			Token::Ident(name) if name == "None" => Ok(Pattern::Literal(Value::None)),
+			Token::Ident(name) if name == "obj" => {
+				match self.next_if(|t| matches!(t, Token::Curly(_))) {
+					Some(Token::Curly(fields)) => self.inner(fields).read_object_pattern(),
+					_ => Ok(Pattern::Binding(name)),
+				}
 			}
-			Some(Ok(Token::Or)) => {
-				self.iter.next();
-
-				let b = self.read_expr().with_context(|| {
-					format!("while reading right side of boolean or expression")
-				})?;
-
-				Expr::Or(expr.into_reach(), b.into_reach())
+			Token::Ident(name) => Ok(Pattern::Binding(name)),
+			Token::StrLit(s) => Ok(Pattern::Literal(Value::String(s))),
+			Token::NumLit(s) => Ok(Pattern::Literal(parse_num(&s)?)),
+			Token::Minus => match self.next_if(|t| matches!(t, Token::NumLit(_))) {
+				Some(Token::NumLit(s)) => Ok(Pattern::Literal(parse_num(&format!("-{s}"))?)),
+				_ => Err(Error::InvalidPattern(Token::Minus)),
+			},
+			Token::Brackets(items) => {
+				let mut parser = self.inner(items);
+				let mut items = Vec::new();
+				while parser.peek().is_some() {
+					let item = match parser.peek() {
+						Some(Ok(Token::DotDot)) => parser.read_rest_pattern()?,
+						_ => parser.read_pattern()?,
+					};
+					if matches!(item, Pattern::Rest(_))
+						&& items.iter().any(|p| matches!(p, Pattern::Rest(_)))
+					{
+						return Err(Error::MultipleRests.at(parser.last_span));
+					}
+					items.push(item);
+				}
+				Ok(Pattern::Array(items))
 			}
-			Some(Ok(Token::And)) => {
-				self.iter.next();
+			token => Err(Error::InvalidPattern(token)),
+		}
+	}
+	/// reads `..name` or `..` in an array pattern
+	fn read_rest_pattern(&mut self) -> Result<Pattern> {
+		match self.next().ok_or(Error::EOFPattern)?? {
+			Token::DotDot => {}
+			token => return Err(Error::InvalidPattern(token).at(self.last_span)),
+		}
+		// `.. a` is a rest without a name followed by `a`
+		let touching = self.peek_span().start == self.last_span.end;
+		match self.next_if(|t| touching && matches!(t, Token::Ident(_))) {
+			Some(Token::Ident(name)) => Ok(Pattern::Rest(Some(name))),
+			_ => Ok(Pattern::Rest(None)),
+		}
+	}
+	/// reads the inside of an object pattern (`obj { name age: a }`)
+	fn read_object_pattern(&mut self) -> Result<Pattern> {
+		let mut fields = Vec::new();
+		while let Some(token) = self.next() {
+			let (key, shorthand) = match token? {
+				Token::Ident(key) => (key, true),
+				Token::StrLit(key) | Token::NumLit(key) => (key, false),
+				_ => return Err(Error::ExpectedIdentObj.at(self.last_span)),
+			};
+			let pattern = match self.peek() {
+				Some(Ok(Token::Colon)) => {
+					self.next();
+					self.read_pattern()
+						.with_context(|| format!("while reading the pattern for key {key:?}"))?
+				}
+				// `obj { name }` is short for `obj { name: name }`
+				_ if shorthand => Pattern::Binding(key.clone()),
+				Some(Err(err)) => return Err(err.clone()),
+				other => {
+					return Err(Error::ExpectedColonObj(other.and_then(Result::ok).cloned())
+						.at(self.last_span));
+				}
+			};
+			fields.push((key, pattern));
+		}
+		Ok(Pattern::Object(fields))
+	}
+	/// reads a value along with any indexing and calls right after it (`a.b.[c](d)`)
+	fn read_postfix(&mut self) -> Result<Expr> {
+		let start = self.peek_span();
+		let expr = match self.peek() {
+			Some(Ok(Token::If)) => self.read_if()?,
+			Some(Ok(Token::Match)) => self.read_match()?,
+			_ => self.read_reach()?.into_expr(),
+		};
+		let mut expr = expr.spanned(start.to(self.last_span));
+		// whether there's a `?.` in the chain, which can cut the rest of it short
+		let mut optional = false;
 
-				let b = self.read_expr().with_context(|| {
-					format!("while reading right side of boolean and operation")
-				})?;
+		loop {
+			let next =
+				self.next_if(|t| matches!(t, Token::Dot | Token::OptionalDot | Token::Parens(_)));
+			expr = match next {
+				Some(dot @ (Token::Dot | Token::OptionalDot)) => {
+					optional |= dot == Token::OptionalDot;
+					let reach = expr.into_reach();
+					let b = self.read_reach().with_context(|| {
+						format!("while reading right-hand side of . indexing access")
+					})?;
 
-				Expr::And(expr.into_reach(), b.into_reach())
-			}
-			Some(Ok(Token::Parens(_))) => match self.iter.next() {
-				Some(Ok(Token::Parens(l))) => {
-					let args = if l.len() > 0 {
-						let mut parser = Parser::from_iter(l.into_iter().map(Ok));
-						let expr = parser.read_expr()?;
-						Some(expr.into_reach())
-					} else {
-						None
+					let index = match b {
+						Reach::Named(name) => IntoIndex::Index(Index::Ident(name)),
+						Reach::Value(Value::i32(i)) => IntoIndex::Index(Index::NumLit(i)),
+						Reach::ArrayLiteral(arr) => match <[Expr; 1]>::try_from(arr) {
+							Ok([i]) => IntoIndex::Expr(Box::new(i)),
+							Err(_) => return Err(Error::InvalidIndex),
+						},
+						_ => return Err(Error::InvalidIndex),
 					};
+
+					match dot {
+						Token::OptionalDot => Expr::OptionalIndex(reach, index),
+						_ => Expr::Index(reach, index),
+					}
+				}
+				Some(Token::Parens(l)) => {
+					let (args, named) = self.inner(l).read_args()?;
 					Expr::CallFn {
 						f: expr.into_reach(),
-						args,
+						args: args.into_iter().map(Expr::into_reach).collect(),
+						named: named
+							.into_iter()
+							.map(|(name, arg)| (name, arg.into_reach()))
+							.collect(),
 					}
 				}
-				_ => panic!("iter.peek() has to be equal to iter.next() this is impossible"),
-			},
-			None | Some(_) => return Err(Error::ExprExpand(expr)),
-		};
-		Ok(a)
+				_ if optional => return Ok(Expr::OptionalChain(Box::new(expr))),
+				_ => return Ok(expr),
+			}
+			.spanned(start.to(self.last_span));
+		}
 	}
-	fn expand_expr(&mut self, expr: Expr) -> Result<Expr> {
-		match self.expand_expr_internal(expr) {
-			Err(Error::ExprExpand(r)) => {
-				// we can't expand further
-				Ok(r)
+	/// reads the body of a lambda, either a single expr or a block
+	fn read_lambda(&mut self, params: Vec<Param>) -> Result<Reach> {
+		let block = self.function_body(|parser| match parser.peek() {
+			Some(Ok(Token::Curly(_))) => parser.read_block(),
+			_ => {
+				let expr = parser
+					.read_expr()
+					.with_context(|| "while reading the body of a lambda".into())?;
+				Ok(Block(vec![Statement::Expr(expr)]))
 			}
-			Err(err) => Err(err),
-			Ok(a) => {
-				// we could expand and we're gonna try to expand again
-				self.expand_expr(a)
+		})?;
+		Ok(Reach::Value(Value::Function(Function { params, block })))
+	}
+	/// reads the parameters of a function declaration (the inside of `fn(a, b = 2)`)
+	fn read_params(&mut self) -> Result<Vec<Param>> {
+		let mut params: Vec<Param> = Vec::new();
+		while let Some(token) = self.next() {
+			let name = match token? {
+				Token::Ident(name) => name,
+				token => return Err(Error::ExpectedIdentGot(token).at(self.last_span)),
+			};
+			if params.iter().any(|param| param.name == name) {
+				return Err(Error::DuplicateArg(name).at(self.last_span));
 			}
+			let default = match self.next_if(|t| *t == Token::Eq) {
+				Some(_) => Some(self.read_expr().with_context(|| {
+					format!("while reading the default value of parameter {name}")
+				})?),
+				None => None,
+			};
+			params.push(Param { name, default });
+			self.read_comma()?;
 		}
+		Ok(params)
 	}
-	pub fn read_expr(&mut self) -> Result<Expr> {
-		let peek = self.iter.peek();
-		let reach = match peek {
-			Some(Ok(Token::If)) => {
-				self.iter.next();
-				let cond = self
-					.read_expr()
-					.with_context(|| format!("while reading condition in if statement"))?;
-
-				let if_true = self
-					.read_block()
-					.with_context(|| format!("while reading if true branch in if statement"))?;
-
-				let if_false = match self.iter.peek() {
-					Some(Ok(Token::Else)) => {
-						self.iter.next();
-						let if_false = self.read_block().with_context(|| {
-							format!("while reading else branch in if statement")
-						})?;
-						Reach::Expr(Box::new(Expr::Block(if_false)))
+	/// reads the arguments of a function call (the inside of `f(a, b, name: c)`), the positional
+	/// ones and the ones passed by name
+	fn read_args(&mut self) -> Result<Args> {
+		let mut args = Vec::new();
+		let mut named: Vec<(String, Expr)> = Vec::new();
+		while self.peek().is_some() {
+			let start = self.peek_span();
+			match self.read_arg_name() {
+				Some(name) => {
+					if named.iter().any(|(other, _)| *other == name) {
+						return Err(Error::DuplicateNamedArg(name).at(start));
 					}
-					_ => Reach::Value(Value::None),
-				};
-
-				Expr::Conditional {
-					condition: Reach::Expr(Box::new(cond)),
-					if_true: Reach::Expr(Box::new(Expr::Block(if_true))),
-					if_false,
+					let arg = self.read_expr().with_context(|| {
+						format!("while reading argument {name} of a function call")
+					})?;
+					named.push((name, arg));
+				}
+				None if !named.is_empty() => {
+					return Err(Error::PositionalAfterNamed.at(start));
+				}
+				None => {
+					let i = args.len();
+					let arg = self.read_expr().with_context(|| {
+						format!("while reading argument {i} of a function call")
+					})?;
+					args.push(arg);
 				}
 			}
-			_ => self.read_reach()?.into_expr(),
+			self.read_comma()?;
+		}
+		Ok((args, named))
+	}
+	/// reads the `name:` in front of an argument passed by name, if it's there
+	fn read_arg_name(&mut self) -> Option<String> {
+		let mut ahead = self.iter.clone();
+		let name = match (ahead.next(), ahead.next()) {
+			(
+				Some(Ok(SpannedToken {
+					token: Token::Ident(name),
+					..
+				})),
+				Some(Ok(SpannedToken {
+					token: Token::Colon,
+					..
+				})),
+			) => name,
+			_ => return None,
 		};
-		// above is if statement parsing
+		// skip the name and the colon
+		self.next();
+		self.next();
+		Some(name)
+	}
+	/// reads the comma after an item in a list, unless the list is over (a trailing comma is fine)
+	fn read_comma(&mut self) -> Result<()> {
+		match self.next() {
+			None | Some(Ok(Token::Comma)) => Ok(()),
+			Some(Ok(token)) => Err(Error::ExpectedCommaGot(token).at(self.last_span)),
+			Some(Err(err)) => Err(err),
+		}
+	}
+	/// reads binary operations whose operators have at least `min_precedence` (see [binary_op])
+	fn read_binary(&mut self, min_precedence: u8) -> Result<Expr> {
+		let start = self.peek_span();
+		let mut lhs = self.read_postfix()?;
 
-		self.expand_expr(reach)
+		loop {
+			let op = match self.peek() {
+				Some(Ok(token)) => binary_op(token),
+				_ => None,
+			};
+			let (precedence, assoc, name, op) = match op {
+				Some(op) if op.0 >= min_precedence => op,
+				_ => return Ok(lhs),
+			};
+			self.next();
+
+			// if everything on the right has to bind tighter, it's left associative
+			let rhs_precedence = match assoc {
+				Assoc::Left => precedence + 1,
+				Assoc::Right => precedence,
+			};
+			let rhs = self
+				.nested(|parser| parser.read_binary(rhs_precedence))
+				.with_context(|| format!("while reading right side of {name}"))?;
+			lhs = op(lhs.into_reach(), rhs.into_reach()).spanned(start.to(self.last_span));
+		}
+	}
+	pub fn read_expr(&mut self) -> Result<Expr> {
+		self.nested(|parser| parser.read_binary(0))
 	}
 	pub fn read_statement(&mut self) -> Result<Statement> {
-		let peek = self.iter.peek().ok_or(Error::EOFStatement)?.clone()?;
-		match peek {
-			Token::Let => {
-				self.iter.next();
-				let name = self.iter.next().ok_or(Error::ExpectedVariableName)??;
-				if let Token::Ident(name) = name {
-					let eq = self.iter.next().ok_or(Error::ExpectedEqLet)??;
-					if let Token::Eq = eq {
-						let expr = self
-							.read_expr()
-							.with_context(|| format!("while declaring variable {name}"))?;
-						return Ok(Statement::SetVariable(name, expr));
+		let start = match self.iter.peek() {
+			None => return Err(Error::EOFStatement),
+			Some(Ok(next)) => next.span,
+			Some(Err(_)) => self.last_span,
+		};
+		let stmt = self
+			.read_statement_unspanned()
+			.map_err(|err| err.at(start))?;
+		Ok(stmt.spanned(start.to(self.last_span)))
+	}
+	fn read_statement_unspanned(&mut self) -> Result<Statement> {
+		let peek = self.peek().ok_or(Error::EOFStatement)?.cloned();
+		match peek.map_err(Clone::clone)? {
+			Token::Let | Token::Const => {
+				let constant = matches!(self.next(), Some(Ok(Token::Const)));
+				let keyword = if constant { "const" } else { "let" };
+				if self.peek().is_none() {
+					return Err(Error::ExpectedVariableName);
+				}
+				let pattern = self
+					.read_pattern()
+					.with_context(|| format!("while reading the variable name after {keyword}"))?;
+				let eq = self.next().ok_or(Error::ExpectedEqLet)??;
+				if let Token::Eq = eq {
+					let expr = self
+						.read_expr()
+						.with_context(|| format!("while declaring variable {pattern}"))?;
+					return Ok(if constant {
+						Statement::SetConst(pattern, expr)
 					} else {
-						return Err(Error::ExpectedEqLet);
-					}
+						Statement::SetVariable(pattern, expr)
+					});
 				} else {
-					return Err(Error::ExpectedVariableName);
+					return Err(Error::ExpectedEqLet);
+				}
+			}
+			Token::Loop | Token::While | Token::For => return self.read_loop(None),
+			Token::Import => return self.read_import(),
+			Token::Label(label) => {
+				self.next();
+				match self.next() {
+					Some(Ok(Token::Colon)) => {}
+					Some(Err(err)) => return Err(err),
+					other => {
+						return Err(Error::ExpectedLoopAfterLabel(
+							label,
+							other.and_then(Result::ok),
+						));
+					}
+				}
+				return match self.peek() {
+					Some(Ok(Token::Loop | Token::While | Token::For)) => {
+						self.read_loop(Some(label))
+					}
+					Some(Err(err)) => Err(err.clone()),
+					other => Err(Error::ExpectedLoopAfterLabel(
+						label,
+						other.and_then(Result::ok).cloned(),
+					)),
+				};
+			}
+			Token::Break => {
+				self.next();
+				let label = self.read_jump_label("break")?;
+				return Ok(Statement::Break(label));
+			}
+			Token::Continue => {
+				self.next();
+				let label = self.read_jump_label("continue")?;
+				return Ok(Statement::Continue(label));
+			}
+			Token::Return => {
+				self.next();
+				if !self.in_fn {
+					return Err(Error::ReturnOutsideFn);
 				}
+				// a bare `return` is followed by the end of the block or another statement
+				let bare = match self.peek() {
+					None => true,
+					Some(Ok(token)) => is_statement_keyword(token),
+					Some(Err(_)) => false,
+				};
+				let expr = if bare {
+					Expr::Reach(Reach::Value(Value::None))
+				} else {
+					self.read_expr()
+						.with_context(|| "while reading the value to return".into())?
+				};
+				return Ok(Statement::Return(expr));
 			}
+			_ => {}
+		}
+
+		let expr = self
+			.read_expr()
+			.with_context(|| format!("while reading an expr in a statement"))?;
+
+		// `=` or a compound assignment like `+=`, which has the operator it stands for
+		let op = match self.peek() {
+			Some(Ok(Token::Eq)) => None,
+			Some(Ok(token)) => match compound_assign_op(token) {
+				Some(op) => Some(op),
+				None => return Ok(Statement::Expr(expr)),
+			},
+			Some(Err(err)) => return Err(err.clone()),
+			None => return Ok(Statement::Expr(expr)),
+		};
+		let (name, path) = assign_target(&expr).ok_or(Error::InvalidAssignTarget)?;
+		self.next();
+
+		let rhs = self
+			.read_expr()
+			.with_context(|| format!("while reading the value to assign to {name}"))?;
+		Ok(Statement::ModifyVariable {
+			name,
+			path,
+			op,
+			expr: rhs,
+		})
+	}
+	/// reads a `loop`, `while` or `for` loop, with the given label
+	fn read_loop(&mut self, label: Option<String>) -> Result<Statement> {
+		match self.next().ok_or(Error::EOFStatement)?? {
 			Token::Loop => {
-				self.iter.next();
-				let b = self
-					.read_block()
+				let block = self
+					.read_loop_block(&label)
 					.with_context(|| format!("while reading loop block"))?;
-				return Ok(Statement::Loop(b));
+				Ok(Statement::Loop { label, block })
 			}
-			Token::Break => {
-				self.iter.next();
-				return Ok(Statement::Break);
+			Token::While => {
+				let condition = self
+					.read_expr()
+					.with_context(|| "while reading condition of while loop".into())?;
+				let block = self
+					.read_loop_block(&label)
+					.with_context(|| "while reading while loop block".into())?;
+				Ok(Statement::While {
+					label,
+					condition,
+					block,
+				})
 			}
 			Token::For => {
-				self.iter.next();
-
-				let v_ident = self
-					.iter
-					.next()
-					.ok_or_else(|| Error::ExpectedIdentFor(None))??;
-				let v_name = match v_ident {
-					Token::Ident(name) => name,
-					other => return Err(Error::ExpectedIdentFor(Some(other))),
-				};
+				if self.peek().is_none() {
+					return Err(Error::ExpectedIdentFor(None));
+				}
+				let pattern = self
+					.read_pattern()
+					.with_context(|| "while reading the variable name of a for loop".into())?;
 
-				let in_token = self
-					.iter
-					.next()
-					.ok_or_else(|| Error::ExpectedInFor(None))??;
+				let in_token = self.next().ok_or_else(|| Error::ExpectedInFor(None))??;
 				match in_token {
 					Token::In => {}
 					other => return Err(Error::ExpectedInFor(Some(other))),
 				}
 
 				let iter = self.read_expr().with_context(|| {
-					format!("as iterator in for loop with variable name {v_name:?}")
+					format!("as iterator in for loop with variable name {pattern}")
 				})?;
 
 				let block = self
-					.read_block()
-					.with_context(|| format!("in a for loop that uses variable name {v_name:?}"))?;
+					.read_loop_block(&label)
+					.with_context(|| format!("in a for loop that uses variable name {pattern}"))?;
 
-				return Ok(Statement::LoopFor {
-					v_name,
+				Ok(Statement::LoopFor {
+					label,
+					pattern,
 					iter,
 					block,
-				});
+				})
 			}
-			_ => {}
+			other => Err(Error::ExpectedLoopAfterLabel(
+				label.unwrap_or_default(),
+				Some(other),
+			)),
 		}
-
-		let expr = self
-			.read_expr()
-			.with_context(|| format!("while reading an expr in a statement"))?;
-		match &expr {
-			Expr::Reach(Reach::Named(name)) => match self.iter.peek().cloned() {
-				Some(Ok(Token::Eq)) => {
-					self.iter.next();
-					let expr = self.read_expr()?;
-					Ok(Statement::ModifyVariable(name.clone(), expr))
+	}
+	/// reads `import "path" as name` or `import { a b } from "path"`
+	fn read_import(&mut self) -> Result<Statement> {
+		self.next();
+		let names = match self.next().transpose()? {
+			Some(Token::StrLit(path)) => {
+				self.read_import_keyword("as")?;
+				return match self.next().transpose()? {
+					Some(Token::Ident(name)) => Ok(Statement::Import {
+						path,
+						names: Imports::As(name),
+					}),
+					other => Err(Error::ExpectedImportName(other)),
+				};
+			}
+			Some(Token::Curly(inner)) => {
+				let mut names = Vec::new();
+				for token in inner {
+					match token.token {
+						Token::Ident(name) if names.contains(&name) => {
+							return Err(Error::DuplicateImport(name).at(token.span));
+						}
+						Token::Ident(name) => names.push(name),
+						other => return Err(Error::ExpectedImportName(Some(other)).at(token.span)),
+					}
 				}
-				Some(Err(err)) => Err(err)?,
-				_ => Ok(Statement::Expr(expr)),
-			},
-			_ => Ok(Statement::Expr(expr)),
+				self.read_import_keyword("from")?;
+				names
+			}
+			other => return Err(Error::ExpectedImportPath(other)),
+		};
+		match self.next().transpose()? {
+			Some(Token::StrLit(path)) => Ok(Statement::Import {
+				path,
+				names: Imports::Only(names),
+			}),
+			other => Err(Error::ExpectedImportPath(other)),
+		}
+	}
+	/// `as` and `from` aren't keywords anywhere else, so they're read as idents
+	fn read_import_keyword(&mut self, keyword: &'static str) -> Result<()> {
+		match self.next().transpose()? {
+			Some(Token::Ident(name)) if name == keyword => Ok(()),
+			other => Err(Error::ExpectedImportKeyword(keyword, other)),
+		}
+	}
+	/// reads the body of a loop, where `break` and `continue` are allowed
+	fn read_loop_block(&mut self, label: &Option<String>) -> Result<Block> {
+		self.loops.push(label.clone());
+		let block = self.read_block();
+		self.loops.pop();
+		block
+	}
+	/// reads the optional label after `break` or `continue`, making sure there's a loop it refers to
+	fn read_jump_label(&mut self, keyword: &'static str) -> Result<Option<String>> {
+		let label = match self.next_if(|t| matches!(t, Token::Label(_))) {
+			Some(Token::Label(label)) => Some(label),
+			_ => None,
+		};
+		match &label {
+			None if self.loops.is_empty() => Err(Error::OutsideLoop(keyword)),
+			Some(label) if !self.loops.contains(&Some(label.clone())) => {
+				Err(Error::UnknownLabel(label.clone()))
+			}
+			_ => Ok(label),
 		}
 	}
 	pub fn read_block(&mut self) -> Result<Block> {
-		match self.iter.next().ok_or(Error::ExpectedBlock)?? {
-			Token::Curly(inner) => {
-				let inner = inner.into_iter().map(Ok).collect::<Vec<_>>();
-				let parser = preproc(Parser::from_iter(inner).statements());
-				let block = parser.collect::<Result<Vec<_>, _>>()?;
+		match self.next().ok_or(Error::ExpectedBlock)?? {
+			Token::Curly(inner) if self.errors.is_some() => self.nested(|parser| {
+				let statements = parser.inner(inner).recover_statements();
+				Ok(Block(preproc(statements).flatten().collect()))
+			}),
+			Token::Curly(inner) => self.nested(|parser| {
+				let statements = parser.inner(inner).statements();
+				let block = preproc(statements).collect::<Result<Vec<_>, _>>()?;
 				Ok(Block(block))
+			}),
+			_ => Err(Error::ExpectedBlock),
+		}
+	}
+	/// runs `f` to read the body of a function, where `return` is allowed. it runs as if we
+	/// weren't in any loops, since loops outside of a function can't be broken out of from inside it
+	fn function_body<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+		let loops = std::mem::take(&mut self.loops);
+		let in_fn = std::mem::replace(&mut self.in_fn, true);
+		let out = f(self);
+		self.loops = loops;
+		self.in_fn = in_fn;
+		out
+	}
+
+	/// reads every statement like [Parser::statements] (and runs them through [preproc]),
+	/// except errors don't stop it \
+	/// after an error it skips ahead to what looks like the start of the next statement, leaves a
+	/// [Statement::Error] where the broken one was and keeps going. blocks get the same treatment,
+	/// so an error inside a function doesn't take the whole function down with it \
+	/// returns the statements along with every error it ran into, in order
+	pub fn recover(mut self) -> (Vec<Statement>, Vec<Error>) {
+		let errors = Rc::new(RefCell::new(Vec::new()));
+		self.errors = Some(errors.clone());
+
+		let statements = self.recover_statements();
+		let statements = preproc(statements).flatten().collect();
+		(statements, errors.take())
+	}
+	/// reads statements until the end, recording errors instead of returning them
+	fn recover_statements(&mut self) -> Vec<Result<Statement>> {
+		let mut statements = Vec::new();
+		loop {
+			match self.iter.peek() {
+				None => return statements,
+				Some(Err(_)) => {
+					// the tokenizer failed, there's no statement to read here
+					if let Some(Err(err)) = self.iter.next() {
+						self.record(err);
+					}
+					continue;
+				}
+				Some(Ok(_)) => {}
+			}
+
+			let start = self.peek_span();
+			let taken = self.taken;
+			match self.read_statement() {
+				Ok(stmt) => statements.push(Ok(stmt)),
+				Err(Error::EOFStatement) => return statements,
+				Err(err) => {
+					self.record(err);
+					// some errors (like the code being nested too deeply) happen before reading
+					// anything, skip at least the token they happened at or we'd get stuck on it
+					if self.taken == taken {
+						self.next();
+					}
+					self.skip_statement();
+					statements.push(Ok(Statement::Error.spanned(start.to(self.last_span))));
+				}
 			}
-			_ => return Err(Error::ExpectedBlock),
+		}
+	}
+	/// skips tokens up until what's probably the start of the next statement: a statement keyword,
+	/// or the first token on a new line
+	fn skip_statement(&mut self) {
+		let line = self.last_span.line;
+		while let Some(Ok(next)) = self.iter.peek() {
+			if is_statement_keyword(&next.token) || next.span.line > line {
+				return;
+			}
+			self.next();
+		}
+	}
+	fn record(&self, err: Error) {
+		if let Some(errors) = &self.errors {
+			errors.borrow_mut().push(err);
 		}
 	}
 
@@ -362,10 +952,163 @@ impl<I: Iterator<Item = Result<Token>> + Clone> Parser<I> {
 	}
 }
 
-pub struct ParserStatements<I: Iterator<Item = Result<Token>> + Clone> {
+/// keywords that start a statement and can't be the start of an expr
+fn is_statement_keyword(token: &Token) -> bool {
+	matches!(
+		token,
+		Token::Let
+			| Token::Const
+			| Token::Loop
+			| Token::While
+			| Token::For
+			| Token::Label(_)
+			| Token::Break
+			| Token::Continue
+			| Token::Return
+			| Token::Import
+	)
+}
+
+/// splits `a.b.[c]` into the name of the variable and the indexes leading into it,
+/// if the expr is something that can be assigned to
+fn assign_target(expr: &Expr) -> Option<(String, Vec<IntoIndex>)> {
+	match expr.unspanned() {
+		Expr::Reach(Reach::Named(name)) => Some((name.clone(), Vec::new())),
+		Expr::Index(reach, index) => {
+			let (name, mut path) = match reach {
+				Reach::Named(name) => (name.clone(), Vec::new()),
+				Reach::Expr(expr) => assign_target(expr)?,
+				_ => return None,
+			};
+			path.push(index.clone());
+			Some((name, path))
+		}
+		_ => None,
+	}
+}
+
+/// the operator a compound assignment operator (`+=`, `-=`...) applies to the old value and the new one
+fn compound_assign_op(token: &Token) -> Option<ArithOp> {
+	let op = match token {
+		Token::PlusEq => ArithOp::Add,
+		Token::MinusEq => ArithOp::Sub,
+		Token::StarEq => ArithOp::Mul,
+		Token::SlashEq => ArithOp::Div,
+		Token::PercentEq => ArithOp::Rem,
+		Token::PowEq => ArithOp::Pow,
+		_ => return None,
+	};
+	Some(op)
+}
+
+/// the arguments of a function call, the positional ones and the ones passed by name
+type Args = (Vec<Expr>, Vec<(String, Expr)>);
+
+/// the precedence, the associativity, the name (for error messages) and the [Expr] of a binary operator
+type BinaryOp = (u8, Assoc, &'static str, fn(Reach, Reach) -> Expr);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Assoc {
+	/// `a - b - c` is `(a - b) - c`
+	Left,
+	/// `a ** b ** c` is `a ** (b ** c)`
+	Right,
+}
+
+/// looks up the [BinaryOp] a token stands for \
+/// from loosest to tightest:
+///
+/// | precedence | operators                      |
+/// |------------|--------------------------------|
+/// | 0          | `??`                           |
+/// | 1          | `\|\|`                          |
+/// | 2          | `&&`                           |
+/// | 3          | `==` `!=` `<` `>` `<=` `>=`    |
+/// | 4          | `..` `..=`                     |
+/// | 5          | `+` `-`                        |
+/// | 6          | `*` `/` `%`                    |
+/// | 7          | `**`                           |
+///
+/// every one of them is left associative except for `**`, so `10 - 3 - 2` is `(10 - 3) - 2`
+/// but `2 ** 3 ** 2` is `2 ** (3 ** 2)`. \
+/// indexing (`a.b`) and function calls (`f(x)`) bind tighter than any of these
+fn binary_op(token: &Token) -> Option<BinaryOp> {
+	let op: BinaryOp = match token {
+		Token::Coalesce => (0, Assoc::Left, "none-coalescing", Expr::Coalesce),
+		Token::Or => (1, Assoc::Left, "boolean or expression", Expr::Or),
+		Token::And => (2, Assoc::Left, "boolean and operation", Expr::And),
+
+		Token::EqEq => (3, Assoc::Left, "equality check", Expr::Cmp),
+		Token::NotEq => (3, Assoc::Left, "inequality check", Expr::NotEq),
+		Token::Gt => (3, Assoc::Left, "greater than check", Expr::Gt),
+		Token::Lt => (3, Assoc::Left, "less than check", Expr::Lt),
+		Token::Ge => (3, Assoc::Left, "greater than or equal check", Expr::Ge),
+		Token::Le => (3, Assoc::Left, "less than or equal check", Expr::Le),
+
+		Token::DotDot => (4, Assoc::Left, "range", Expr::Range),
+		Token::DotDotEq => (4, Assoc::Left, "inclusive range", Expr::RangeInclusive),
+
+		Token::Plus => (5, Assoc::Left, "addition", Expr::Add),
+		Token::Minus => (5, Assoc::Left, "subtraction", Expr::Sub),
+
+		Token::Star => (6, Assoc::Left, "multiplication", Expr::Mul),
+		Token::Slash => (6, Assoc::Left, "division", Expr::Div),
+		Token::Percent => (6, Assoc::Left, "remainder", Expr::Rem),
+
+		Token::Pow => (7, Assoc::Right, "power", Expr::Pow),
+		_ => return None,
+	};
+	Some(op)
+}
+
+/// parses the text of a number literal (with a `-` in front if it's negative) \
+/// supports `_` separators, `0x`/`0o`/`0b` prefixes, fractions and exponents
+fn parse_num(lit: &str) -> Result<Value> {
+	let invalid = || Error::InvalidNumLit(lit.into());
+
+	let (negative, digits) = match lit.strip_prefix('-') {
+		Some(digits) => (true, digits),
+		None => (false, lit),
+	};
+	let digits = digits.replace('_', "");
+	let radix = match digits.get(..2) {
+		Some("0x" | "0X") => 16,
+		Some("0o" | "0O") => 8,
+		Some("0b" | "0B") => 2,
+		_ => 10,
+	};
+
+	if radix == 10 && digits.contains(['.', 'e', 'E']) {
+		let a: f32 = digits.parse().map_err(|_| invalid())?;
+		return Ok(Value::f32(if negative { -a } else { a }));
+	}
+
+	let digits = if radix == 10 { &digits } else { &digits[2..] };
+	if !digits.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+		// from_str_radix would accept a sign here
+		return Err(invalid());
+	}
+	// the sign gets parsed along with the digits so i32::MIN still fits
+	let signed = if negative {
+		format!("-{digits}")
+	} else {
+		digits.to_string()
+	};
+	match i32::from_str_radix(&signed, radix) {
+		Ok(a) => Ok(Value::i32(a)),
+		Err(err) => match err.kind() {
+			IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+				Err(Error::IntLitOverflow(lit.into()))
+			}
+			_ => Err(invalid()),
+		},
+	}
+}
+
+pub struct ParserStatements<I: Iterator<Item = Result<SpannedToken>> + Clone> {
 	parser: Parser<I>,
 }
-impl<I: Iterator<Item = Result<Token>> + Clone> Iterator for ParserStatements<I> {
+impl<I: Iterator<Item = Result<SpannedToken>> + Clone> Iterator for ParserStatements<I> {
 	type Item = Result<Statement>;
 
 	fn next(&mut self) -> Option<Self::Item> {
@@ -378,10 +1121,10 @@ impl<I: Iterator<Item = Result<Token>> + Clone> Iterator for ParserStatements<I>
 
 /// comma separated expressions \
 /// expect they're really not even separated by a comma but shshsh
-pub struct ParserCSE<I: Iterator<Item = Result<Token>> + Clone> {
+pub struct ParserCSE<I: Iterator<Item = Result<SpannedToken>> + Clone> {
 	parser: Parser<I>,
 }
-impl<I: Iterator<Item = Result<Token>> + Clone> Iterator for ParserCSE<I> {
+impl<I: Iterator<Item = Result<SpannedToken>> + Clone> Iterator for ParserCSE<I> {
 	type Item = Result<Expr>;
 
 	fn next(&mut self) -> Option<Self::Item> {
@@ -393,14 +1136,14 @@ impl<I: Iterator<Item = Result<Token>> + Clone> Iterator for ParserCSE<I> {
 	}
 }
 
-pub struct ParserObj<I: Iterator<Item = Result<Token>> + Clone> {
+pub struct ParserObj<I: Iterator<Item = Result<SpannedToken>> + Clone> {
 	parser: Parser<I>,
 }
-impl<I: Iterator<Item = Result<Token>> + Clone> Iterator for ParserObj<I> {
+impl<I: Iterator<Item = Result<SpannedToken>> + Clone> Iterator for ParserObj<I> {
 	type Item = Result<(String, Expr)>;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		let name = match self.parser.iter.next()? {
+		let name = match self.parser.next()? {
 			Ok(Token::Ident(name) | Token::StrLit(name)) => name,
 			Ok(Token::NumLit(name)) => name,
 			Err(err) => {
@@ -411,7 +1154,7 @@ impl<I: Iterator<Item = Result<Token>> + Clone> Iterator for ParserObj<I> {
 			Ok(_) => return Some(Err(Error::ExpectedIdentObj)),
 		};
 
-		let colon = self.parser.iter.next();
+		let colon = self.parser.next();
 		match colon {
 			Some(Ok(Token::Colon)) => {
 				// yippee
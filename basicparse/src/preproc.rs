@@ -5,12 +5,23 @@ pub fn preproc<E>(
 ) -> impl Iterator<Item = Result<Statement, E>> {
 	let iter = iter.into_iter();
 	iter.map(|a| match a {
-		Ok(Statement::Expr(Expr::Reach(Reach::Value(Value::String(s))))) => {
+		Ok(stmt) => {
 			// inline unused strings get converted into hidden debug statements
-			Ok(match s.as_ref() {
-				"__pause" => Statement::Pause,
-				"__dump_ctx" => Statement::DumpContext,
-				_ => Statement::Expr(Expr::Reach(Reach::Value(Value::String(s)))),
+			let debug = match stmt.unspanned() {
+				Statement::Expr(expr) => match expr.unspanned() {
+					Expr::Reach(Reach::Value(Value::String(s))) => match s.as_ref() {
+						"__pause" => Some(Statement::Pause),
+						"__dump_ctx" => Some(Statement::DumpContext),
+						_ => None,
+					},
+					_ => None,
+				},
+				_ => None,
+			};
+			Ok(match (debug, stmt.span()) {
+				(Some(debug), Some(span)) => debug.spanned(span),
+				(Some(debug), None) => debug,
+				(None, _) => stmt,
 			})
 		}
 		_ => a,
@@ -1,37 +1,70 @@
 use crate::*;
 
 use iter_read_until::{IntoReader, Reader, StrReader};
+use langlib::Span;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum Signal {
 	Eq,
 	Plus,
 	Minus,
+	/// `*`, or the start of `**`
+	Star,
+	Percent,
 	Gt,
 	Lt,
+	/// `!`, or the start of `!=`
+	Bang,
+	/// `|`, the start of `||`
+	Pipe,
+	/// `&`, the start of `&&`
+	Amp,
+	/// `?`, the start of `?.` or `??`
+	Question,
 
 	Dot,
 	Colon,
+	Comma,
+
+	/// `/`, either a comment or a division
+	Slash,
 
 	StrStart,
 	ParensStart,
 	CurlyStart,
 	BracketStart,
+
+	ParensEnd,
+	CurlyEnd,
+	BracketEnd,
 }
 fn token_letters(c: u8) -> Option<Signal> {
 	match c {
 		b'=' => Some(Signal::Eq),
 		b'+' => Some(Signal::Plus),
 		b'-' => Some(Signal::Minus),
+		b'*' => Some(Signal::Star),
+		b'%' => Some(Signal::Percent),
 		b'>' => Some(Signal::Gt),
 		b'<' => Some(Signal::Lt),
+		b'!' => Some(Signal::Bang),
+		b'|' => Some(Signal::Pipe),
+		b'&' => Some(Signal::Amp),
+		b'?' => Some(Signal::Question),
 		b'.' => Some(Signal::Dot),
 		b':' => Some(Signal::Colon),
+		b',' => Some(Signal::Comma),
+
+		b'/' => Some(Signal::Slash),
 
 		b'"' => Some(Signal::StrStart),
 		b'(' => Some(Signal::ParensStart),
 		b'{' => Some(Signal::CurlyStart),
 		b'[' => Some(Signal::BracketStart),
+
+		b')' => Some(Signal::ParensEnd),
+		b'}' => Some(Signal::CurlyEnd),
+		b']' => Some(Signal::BracketEnd),
 		_ => None,
 	}
 }
@@ -41,6 +74,8 @@ pub enum Token {
 	// --- keywords
 	/// `let`
 	Let,
+	/// `const`
+	Const,
 	/// `fn`
 	Fn,
 
@@ -48,127 +83,362 @@ pub enum Token {
 	If,
 	/// `else`
 	Else,
+	/// `match`
+	Match,
 
 	/// `loop`
 	Loop,
+	/// `while`
+	While,
 	/// `break`
 	Break,
+	/// `continue`
+	Continue,
 	/// `for`
 	For,
 	/// `in`
 	In,
-
-	// -- treated as keywords too
-	/// `||`
-	Or,
-	/// `&&`
-	And,
+	/// `return`
+	Return,
+	/// `import`
+	Import,
 
 	// - signals
 	/// `.`
 	Dot,
+	/// `?.`, indexing that gives None instead of an error if there's nothing to index into
+	OptionalDot,
+	/// `..`, a range (`0..10`) or the rest of an array pattern (`[head ..tail]`)
+	DotDot,
+	/// `..=`, a range that includes its end (`0..=10`)
+	DotDotEq,
 	/// `:`
 	Colon,
+	/// `,`
+	Comma,
 	/// `=`
 	Eq,
+	/// `=>`
+	FatArrow,
 	/// `+`
 	Plus,
 	/// `-`
 	Minus,
+	/// `*`
+	Star,
+	/// `/`
+	Slash,
+	/// `%`
+	Percent,
+	/// `**`
+	Pow,
+	/// `+=`
+	PlusEq,
+	/// `-=`
+	MinusEq,
+	/// `*=`
+	StarEq,
+	/// `/=`
+	SlashEq,
+	/// `%=`
+	PercentEq,
+	/// `**=`
+	PowEq,
 	/// `>`
 	Gt,
 	/// `<`
 	Lt,
+	/// `==`
+	EqEq,
+	/// `!=`
+	NotEq,
+	/// `>=`
+	Ge,
+	/// `<=`
+	Le,
+	/// `!`
+	Not,
+	/// `|`, around the parameters of a lambda (`|x| x + 1`)
+	Pipe,
+	/// `||`
+	Or,
+	/// `&&`
+	And,
+	/// `??`
+	Coalesce,
 
 	Ident(String),
+	/// `'name`, a loop label (without the `'`)
+	Label(String),
 	/// number literal
 	NumLit(String),
 	/// string literal,
 	StrLit(String),
+	/// format string literal (`f"..."`), split into the literal text and the tokens of the expressions
+	FmtStr(Vec<StrPart>),
 
 	/// everything between `(` and `)`, tokenized
-	Parens(Vec<Token>),
+	Parens(Vec<SpannedToken>),
 	/// everything between `{` and `}`, tokenized
-	Curly(Vec<Token>),
+	Curly(Vec<SpannedToken>),
 	/// everything between `[` and `]`, tokenized
-	Brackets(Vec<Token>),
+	Brackets(Vec<SpannedToken>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// a piece of a format string literal
+pub enum StrPart {
+	Lit(String),
+	/// everything between `{` and `}`, tokenized, along with the span of the `{`
+	Expr(Span, Vec<SpannedToken>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// a [Token] along with where it is in the source code
+pub struct SpannedToken {
+	pub token: Token,
+	pub span: Span,
 }
 
+#[derive(Copy, Clone, Debug)]
+/// a byte offset with the line and column it's on, used to calculate [Span]s
+struct Position {
+	offset: usize,
+	line: usize,
+	col: usize,
+}
+impl Position {
+	fn start() -> Self {
+		Self {
+			offset: 0,
+			line: 1,
+			col: 1,
+		}
+	}
+	/// walks forward to `offset`, counting lines and (utf-8) characters on the way
+	fn advance(&mut self, src: &str, offset: usize) {
+		for &c in &src.as_bytes()[self.offset..offset] {
+			if c == b'\n' {
+				self.line += 1;
+				self.col = 1;
+			} else if c & 0b1100_0000 != 0b1000_0000 {
+				self.col += 1;
+			}
+		}
+		self.offset = offset;
+	}
+}
+
+/// what reading the source code can result in. closing brackets only make sense
+/// when reading a bracket group, so they never leave the tokenizer
+enum Lexeme {
+	Token(SpannedToken),
+	/// a closing bracket and its span
+	Close(Signal, Span),
+}
+
+/// true if the error only means the tokenizer ran out of source code
+fn is_finished(err: &Error) -> bool {
+	matches!(
+		err,
+		Error::Read(readuntil_ext::Error::ExpectedOkOrEndGot(
+			iter_read_until::Read::Finished,
+		)) | Error::Read(readuntil_ext::Error::ExpectedGot {
+			expected: _,
+			got: iter_read_until::Read::Finished,
+		}) | Error::TokenizerFinished
+	)
+}
+
+/// how many brackets deep the source code can go
+const MAX_NESTING: usize = 64;
+
 #[derive(Copy, Clone, Debug)]
 pub struct Tokenizer<'a> {
 	reader: StrReader<'a>,
 	/// we use signal to store any additional information we need to take care of before reading from the reader
 	signal: Option<Signal>,
+	/// byte offset of the character that caused the signal
+	signal_at: usize,
+	/// true if the last token was a [Token::Dot], so `array.0.1` doesn't get read as `array` `.` `0.1`
+	after_dot: bool,
+	/// how many brackets deep we are, see [MAX_NESTING]
+	depth: usize,
+
+	/// the source code, every [Span] is relative to this
+	src: &'a str,
+	pos: Position,
 }
 impl<'a> Tokenizer<'a> {
 	pub fn new(src: &'a str) -> Self {
 		Tokenizer {
 			reader: src.reader(),
 			signal: None,
+			signal_at: 0,
+			after_dot: false,
+			depth: 0,
+			src,
+			pos: Position::start(),
 		}
 	}
 
-	pub fn next_token(&mut self) -> Result<Token> {
-		if let Some(signal) = self.signal.take() {
-			/// shared macro for all bracket types
-			macro_rules! encapsulating {
-				($op:expr, $cl:expr => $name:ident) => {{
-					let mut i = 1;
-					let s = self
-						.reader
-						.read_until(|c| match *c {
-							$op => {
-								i += 1;
-								false
-							}
-							$cl => {
-								i -= 1;
-								if i <= 0 { true } else { false }
+	/// byte offset of `slice` in the source code
+	fn offset_of(&self, slice: &str) -> usize {
+		slice.as_ptr() as usize - self.src.as_ptr() as usize
+	}
+	/// the position only ever moves forward, so `start` can't be before the start of
+	/// the last span we made. make the span of a token before reading what's inside it
+	fn span(&mut self, start: usize, end: usize) -> Span {
+		debug_assert!(start >= self.pos.offset, "span start went backwards");
+		if start > self.pos.offset {
+			self.pos.advance(self.src, start);
+		}
+		Span {
+			start,
+			end,
+			line: self.pos.line,
+			col: self.pos.col,
+		}
+	}
+	/// the byte right after the signal character, if there is one
+	fn after_signal(&self) -> Option<u8> {
+		self.src.as_bytes().get(self.signal_at + 1).copied()
+	}
+	/// skips the next byte in the reader
+	fn skip_byte(&mut self) {
+		self.reader.read_until(|_| true);
+	}
+	/// if the signal character is followed by `c`, skips it and returns true
+	fn followed_by(&mut self, c: u8) -> bool {
+		if self.after_signal() == Some(c) {
+			self.skip_byte();
+			true
+		} else {
+			false
+		}
+	}
+
+	/// resolves escape sequences in the inside of a string literal
+	fn unescape(&mut self, s: &str) -> Result<String> {
+		let offset = self.offset_of(s);
+		let mut out = String::with_capacity(s.len());
+
+		let mut chars = s.char_indices().peekable();
+		while let Some((i, c)) = chars.next() {
+			if c != '\\' {
+				out.push(c);
+				continue;
+			}
+			let escaped = match chars.next() {
+				Some((_, 'n')) => '\n',
+				Some((_, 't')) => '\t',
+				Some((_, 'r')) => '\r',
+				Some((_, '0')) => '\0',
+				Some((_, '\\')) => '\\',
+				Some((_, '"')) => '"',
+				Some((_, '{')) => '{',
+				Some((_, '}')) => '}',
+				Some((_, '\n' | '\r')) => {
+					// line continuation, skips the line break and the indentation after it
+					while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+					continue;
+				}
+				Some((_, 'u')) => {
+					let mut end = i + 2;
+					let mut hex = None;
+					if chars.next_if(|(_, c)| *c == '{').is_some() {
+						end += 1;
+						for (j, c) in chars.by_ref() {
+							end = j + c.len_utf8();
+							if c == '}' {
+								hex = Some(&s[i + 3..j]);
+								break;
 							}
-							_ => false,
-						})
-						.ok()
-						.with_context(|| {
-							format!(
-								"couldn't find matching closing parens for {} ('{}')",
-								stringify!($name),
-								$cl as char
-							)
-						})?;
-					let tokenizer = Tokenizer::new(s);
-					let tokens = tokenizer.collect::<Result<Vec<_>, _>>()?;
-					Token::$name(tokens)
-				}};
+						}
+					}
+					let c = hex
+						.and_then(|hex| u32::from_str_radix(hex, 16).ok())
+						.and_then(char::from_u32);
+					match c {
+						Some(c) => c,
+						None => {
+							let escape = s[i..end].to_string();
+							let span = self.span(offset + i, offset + end);
+							return Err(Error::InvalidEscape(escape).at(span));
+						}
+					}
+				}
+				other => {
+					let end = other.map(|(j, c)| j + c.len_utf8()).unwrap_or(i + 1);
+					let escape = s[i..end].to_string();
+					let span = self.span(offset + i, offset + end);
+					return Err(Error::InvalidEscape(escape).at(span));
+				}
+			};
+			out.push(escaped);
+		}
+		Ok(out)
+	}
+
+	pub fn next_token(&mut self) -> Result<SpannedToken> {
+		match self.next_lexeme()? {
+			Lexeme::Token(token) => Ok(token),
+			Lexeme::Close(signal, span) => {
+				Err(Error::UnexpectedClosingBracket(closing_char(signal)).at(span))
 			}
+		}
+	}
 
-			return Ok(match signal {
-				Signal::Eq => Token::Eq,
-				Signal::Plus => Token::Plus,
-				Signal::Minus => Token::Minus,
-				Signal::Gt => Token::Gt,
-				Signal::Lt => Token::Lt,
-				Signal::Dot => Token::Dot,
-				Signal::Colon => Token::Colon,
-				Signal::StrStart => {
-					let s = self
-						.reader
-						.read_until_item(b'"')
-						.ok()
-						.with_context(|| "you didn't close a string literal".into())?;
-					Token::StrLit(s.into())
-				}
-				Signal::ParensStart => {
-					encapsulating!(b'(', b')' => Parens)
+	/// reads tokens until the closing bracket `close`
+	fn group(&mut self, close: Signal, open_span: Span) -> Result<(Vec<SpannedToken>, usize)> {
+		if self.depth >= MAX_NESTING {
+			return Err(Error::TooDeep(MAX_NESTING).at(open_span));
+		}
+		self.depth += 1;
+		let group = self.group_inner(close, open_span);
+		self.depth -= 1;
+		group
+	}
+	fn group_inner(
+		&mut self,
+		close: Signal,
+		open_span: Span,
+	) -> Result<(Vec<SpannedToken>, usize)> {
+		let mut tokens = Vec::new();
+		loop {
+			match self.next_lexeme() {
+				Ok(Lexeme::Token(token)) => tokens.push(token),
+				Ok(Lexeme::Close(signal, span)) if signal == close => {
+					return Ok((tokens, span.end));
 				}
-				Signal::CurlyStart => {
-					encapsulating!(b'{', b'}' => Curly)
+				Ok(Lexeme::Close(signal, span)) => {
+					return Err(Error::MismatchedClosingBracket {
+						expected: closing_char(close),
+						got: closing_char(signal),
+					}
+					.at(span));
 				}
-				Signal::BracketStart => {
-					encapsulating!(b'[', b']' => Brackets)
+				Err(err) if is_finished(&err) => {
+					return Err(Error::UnclosedBracket(closing_char(close)).at(open_span));
 				}
-			});
+				Err(err) => return Err(err),
+			}
 		}
+	}
 
+	fn next_lexeme(&mut self) -> Result<Lexeme> {
+		let lexeme = self.read_lexeme()?;
+		self.after_dot = matches!(
+			lexeme,
+			Lexeme::Token(SpannedToken {
+				token: Token::Dot | Token::OptionalDot,
+				..
+			})
+		);
+		Ok(lexeme)
+	}
+	/// reads until whitespace or a signal character
+	fn read_word(&mut self) -> Result<&'a str> {
 		let word = self
 			.reader
 			.read_until(|c| {
@@ -183,56 +453,635 @@ impl<'a> Tokenizer<'a> {
 				}
 			})
 			.ok_or_end()?;
-		if word.len() == 0 {
-			if self.reader.s.len() == self.reader.i {
+		if self.signal.is_some() {
+			self.signal_at = self.offset_of(word) + word.len();
+		}
+		Ok(word)
+	}
+	/// `word` starts with a digit, reads the rest of the number literal (fractions and exponents
+	/// get split up by signals) and returns where it ends
+	fn read_num(&mut self, word: &'a str) -> Result<usize> {
+		let start = self.offset_of(word);
+		let mut end = start + word.len();
+
+		let lower = word.to_ascii_lowercase();
+		let decimal =
+			!(lower.starts_with("0x") || lower.starts_with("0o") || lower.starts_with("0b"));
+		let mut fraction = self.after_dot || !decimal; // true if we can't have a fraction anymore
+		loop {
+			let next_is_digit = self.after_signal().is_some_and(|c| c.is_ascii_digit());
+			let last = self.src.as_bytes()[end - 1];
+			let continues = match self.signal {
+				Some(Signal::Dot) => !fraction && next_is_digit,
+				Some(Signal::Plus | Signal::Minus) => {
+					decimal && (last == b'e' || last == b'E') && next_is_digit
+				}
+				_ => false,
+			};
+			if !continues {
+				return Ok(end);
+			}
+			// once we're past the dot or the exponent, there can't be any more dots
+			fraction = true;
+
+			self.signal = None;
+			let rest = self.read_word()?;
+			end = self.offset_of(rest) + rest.len();
+		}
+	}
+
+	fn read_lexeme(&mut self) -> Result<Lexeme> {
+		if let Some(signal) = self.signal.take() {
+			let start = self.signal_at;
+			let signal_span = self.span(start, start + 1);
+
+			let (token, end) = match signal {
+				Signal::Eq if self.followed_by(b'=') => (Token::EqEq, start + 2),
+				Signal::Eq if self.followed_by(b'>') => (Token::FatArrow, start + 2),
+				Signal::Eq => (Token::Eq, start + 1),
+				Signal::Plus if self.followed_by(b'=') => (Token::PlusEq, start + 2),
+				Signal::Plus => (Token::Plus, start + 1),
+				Signal::Minus if self.followed_by(b'=') => (Token::MinusEq, start + 2),
+				Signal::Minus => (Token::Minus, start + 1),
+				Signal::Star if self.followed_by(b'*') => {
+					if self.src.as_bytes().get(start + 2) == Some(&b'=') {
+						self.skip_byte();
+						(Token::PowEq, start + 3)
+					} else {
+						(Token::Pow, start + 2)
+					}
+				}
+				Signal::Star if self.followed_by(b'=') => (Token::StarEq, start + 2),
+				Signal::Star => (Token::Star, start + 1),
+				Signal::Percent if self.followed_by(b'=') => (Token::PercentEq, start + 2),
+				Signal::Percent => (Token::Percent, start + 1),
+				Signal::Gt if self.followed_by(b'=') => (Token::Ge, start + 2),
+				Signal::Gt => (Token::Gt, start + 1),
+				Signal::Lt if self.followed_by(b'=') => (Token::Le, start + 2),
+				Signal::Lt => (Token::Lt, start + 1),
+				Signal::Bang if self.followed_by(b'=') => (Token::NotEq, start + 2),
+				Signal::Bang => (Token::Not, start + 1),
+				Signal::Pipe if self.followed_by(b'|') => (Token::Or, start + 2),
+				Signal::Pipe => (Token::Pipe, start + 1),
+				Signal::Amp if self.followed_by(b'&') => (Token::And, start + 2),
+				Signal::Question if self.followed_by(b'.') => (Token::OptionalDot, start + 2),
+				Signal::Question if self.followed_by(b'?') => (Token::Coalesce, start + 2),
+				Signal::Amp | Signal::Question => {
+					let c = self.src.as_bytes()[start] as char;
+					return Err(Error::InvalidChar(c).at(signal_span));
+				}
+				Signal::Dot if self.followed_by(b'.') => {
+					if self.src.as_bytes().get(start + 2) == Some(&b'=') {
+						self.skip_byte();
+						(Token::DotDotEq, start + 3)
+					} else {
+						(Token::DotDot, start + 2)
+					}
+				}
+				Signal::Dot => (Token::Dot, start + 1),
+				Signal::Colon => (Token::Colon, start + 1),
+				Signal::Comma => (Token::Comma, start + 1),
+				Signal::Slash => {
+					match self.after_signal() {
+						Some(b'/') => {
+							// line comment
+							self.reader.read_until_item(b'\n');
+							return self.read_lexeme();
+						}
+						Some(b'*') => {
+							// block comment, these nest
+							self.skip_byte();
+							let mut depth = 1;
+							let mut prev = 0;
+							self.reader
+								.read_until(|c| {
+									match (prev, *c) {
+										(b'/', b'*') => {
+											depth += 1;
+											prev = 0;
+										}
+										(b'*', b'/') => {
+											depth -= 1;
+											prev = 0;
+										}
+										(_, c) => prev = c,
+									}
+									depth == 0
+								})
+								.ok()
+								.map_err(|_| Error::UnclosedComment.at(signal_span))?;
+							return self.read_lexeme();
+						}
+						Some(b'=') => {
+							self.skip_byte();
+							(Token::SlashEq, start + 2)
+						}
+						_ => (Token::Slash, start + 1),
+					}
+				}
+				Signal::StrStart => {
+					let mut escaped = false;
+					let s = self
+						.reader
+						.read_until(|c| {
+							if escaped {
+								escaped = false;
+								false
+							} else {
+								escaped = *c == b'\\';
+								*c == b'"'
+							}
+						})
+						.ok()
+						.with_context(|| "you didn't close a string literal".into())
+						.map_err(|err| err.at(signal_span))?;
+					let end = self.offset_of(s) + s.len() + 1;
+					(Token::StrLit(self.unescape(s)?), end)
+				}
+				Signal::ParensStart => {
+					let (tokens, end) = self.group(Signal::ParensEnd, signal_span)?;
+					(Token::Parens(tokens), end)
+				}
+				Signal::CurlyStart => {
+					let (tokens, end) = self.group(Signal::CurlyEnd, signal_span)?;
+					(Token::Curly(tokens), end)
+				}
+				Signal::BracketStart => {
+					let (tokens, end) = self.group(Signal::BracketEnd, signal_span)?;
+					(Token::Brackets(tokens), end)
+				}
+				Signal::ParensEnd | Signal::CurlyEnd | Signal::BracketEnd => {
+					return Ok(Lexeme::Close(signal, signal_span));
+				}
+			};
+			return Ok(Lexeme::Token(SpannedToken {
+				token,
+				span: Span { end, ..signal_span },
+			}));
+		}
+
+		let word = self.read_word()?;
+		let start = self.offset_of(word);
+		if word.is_empty() {
+			if self.signal.is_none() && self.reader.s.len() == self.reader.i {
 				return Err(Error::TokenizerFinished);
 			} else {
-				return self.next_token();
+				return self.read_lexeme();
 			}
 		}
+		if self.signal == Some(Signal::StrStart) && word == "f" {
+			// f"text {expr} text"
+			self.signal = None;
+			let open_span = self.span(start, start + 2);
 
-		match word.trim() {
-			"let" => Ok(Token::Let),
-			"fn" => Ok(Token::Fn),
-			"if" => Ok(Token::If),
-			"else" => Ok(Token::Else),
-			"loop" => Ok(Token::Loop),
-			"break" => Ok(Token::Break),
-			"for" => Ok(Token::For),
-			"in" => Ok(Token::In),
-			"||" => Ok(Token::Or),
-			"&&" => Ok(Token::And),
-			ident => {
-				let number = ident
-					.chars()
-					.map(|a| a.is_ascii_digit() || a == '.')
-					.fold(true, |a, b| a && b); // if all characters are digits or dots
-				let numlit = number && ident.chars().filter(|a| *a == '.').count() <= 1; // if number && there's at most one dot
-
-				if numlit {
-					Ok(Token::NumLit(ident.into()))
-				} else {
-					Ok(Token::Ident(ident.into()))
+			let mut parts = Vec::new();
+			let end = loop {
+				let mut escaped = false;
+				let s = self
+					.reader
+					.read_until(|c| {
+						if escaped {
+							escaped = false;
+							false
+						} else {
+							escaped = *c == b'\\';
+							*c == b'"' || *c == b'{'
+						}
+					})
+					.ok()
+					.with_context(|| "you didn't close a format string literal".into())
+					.map_err(|err| err.at(open_span))?;
+				let lit = self.unescape(s)?;
+				if !lit.is_empty() {
+					parts.push(StrPart::Lit(lit));
 				}
-			}
+
+				let delim_at = self.offset_of(s) + s.len();
+				if self.src.as_bytes()[delim_at] == b'"' {
+					break delim_at + 1;
+				}
+				let brace_span = self.span(delim_at, delim_at + 1);
+				let (tokens, _) = self.group(Signal::CurlyEnd, brace_span)?;
+				parts.push(StrPart::Expr(brace_span, tokens));
+			};
+
+			return Ok(Lexeme::Token(SpannedToken {
+				token: Token::FmtStr(parts),
+				span: Span { end, ..open_span },
+			}));
 		}
+		if self.signal == Some(Signal::StrStart) && is_raw_prefix(word) {
+			// r"..." or r#"..."#
+			self.signal = None;
+			let open_span = self.span(start, start + word.len() + 1);
+			let hashes = word.len() - 1;
+
+			// how many #s we've seen since the last quote, if we've seen a quote
+			let mut closing = None;
+			let s = self
+				.reader
+				.read_until(|c| {
+					closing = match (closing, *c) {
+						(_, b'"') => Some(0),
+						(Some(n), b'#') => Some(n + 1),
+						_ => None,
+					};
+					closing == Some(hashes)
+				})
+				.ok()
+				.with_context(|| "you didn't close a raw string literal".into())
+				.map_err(|err| err.at(open_span))?;
+			let end = self.offset_of(s) + s.len() + 1;
+			let s = &s[..s.len() - hashes]; // the closing quote and hashes aren't part of the string
+
+			return Ok(Lexeme::Token(SpannedToken {
+				token: Token::StrLit(s.into()),
+				span: Span { end, ..open_span },
+			}));
+		}
+
+		if word.starts_with(|c: char| c.is_ascii_digit()) {
+			let end = self.read_num(word)?;
+			return Ok(Lexeme::Token(SpannedToken {
+				token: Token::NumLit(self.src[start..end].into()),
+				span: self.span(start, end),
+			}));
+		}
+
+		let token = match word.trim() {
+			"let" => Token::Let,
+			"const" => Token::Const,
+			"fn" => Token::Fn,
+			"if" => Token::If,
+			"else" => Token::Else,
+			"match" => Token::Match,
+			"loop" => Token::Loop,
+			"while" => Token::While,
+			"break" => Token::Break,
+			"continue" => Token::Continue,
+			"for" => Token::For,
+			"in" => Token::In,
+			"return" => Token::Return,
+			"import" => Token::Import,
+			label if label.starts_with('\'') => {
+				let name = &label[1..];
+				if name.is_empty() || !name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_')
+				{
+					return Err(
+						Error::InvalidLabel(label.into()).at(self.span(start, start + word.len()))
+					);
+				}
+				Token::Label(name.into())
+			}
+			ident => Token::Ident(ident.into()),
+		};
+		Ok(Lexeme::Token(SpannedToken {
+			token,
+			span: self.span(start, start + word.len()),
+		}))
+	}
+}
+/// `r`, `r#`, `r##`...
+fn is_raw_prefix(word: &str) -> bool {
+	word.starts_with('r') && word[1..].bytes().all(|c| c == b'#')
+}
+fn closing_char(signal: Signal) -> char {
+	match signal {
+		Signal::ParensEnd => ')',
+		Signal::CurlyEnd => '}',
+		Signal::BracketEnd => ']',
+		_ => '?',
 	}
 }
 impl<'a> Iterator for Tokenizer<'a> {
-	type Item = Result<Token>;
+	type Item = Result<SpannedToken>;
 
 	fn next(&mut self) -> Option<Self::Item> {
 		match self.next_token() {
 			Ok(a) => Some(Ok(a)),
-			Err(Error::Read(readuntil_ext::Error::ExpectedOkOrEndGot(
-				iter_read_until::Read::Finished,
-			))) => None,
-			Err(Error::Read(readuntil_ext::Error::ExpectedGot {
-				expected: _,
-				got: iter_read_until::Read::Finished,
-			})) => None,
-			Err(Error::TokenizerFinished) => None,
+			Err(err) if is_finished(&err) => None,
 			Err(err) => Some(Err(err)),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// every token in `src`, panicking on errors
+	fn tokens(src: &str) -> Vec<Token> {
+		Tokenizer::new(src)
+			.map(|token| token.expect("failed to tokenize").token)
+			.collect()
+	}
+	/// the first error tokenizing `src` runs into
+	fn error(src: &str) -> Error {
+		Tokenizer::new(src)
+			.find_map(Result::err)
+			.expect("expected an error")
+	}
+	fn span(start: usize, end: usize, line: usize, col: usize) -> Span {
+		Span {
+			start,
+			end,
+			line,
+			col,
+		}
+	}
+
+	#[test]
+	fn tokens_know_where_they_are() {
+		let spans: Vec<_> = Tokenizer::new("let a =\n  a + 10")
+			.map(|token| token.unwrap().span)
+			.collect();
+		assert_eq!(
+			spans,
+			[
+				span(0, 3, 1, 1),
+				span(4, 5, 1, 5),
+				span(6, 7, 1, 7),
+				span(10, 11, 2, 3),
+				span(12, 13, 2, 5),
+				span(14, 16, 2, 7),
+			]
+		);
+
+		// columns count characters, not bytes
+		let token = Tokenizer::new("\"äöü\" x").nth(1).unwrap().unwrap();
+		assert_eq!(token.span, span(9, 10, 1, 7));
+	}
+
+	#[test]
+	fn tokens_in_groups_know_where_they_are() {
+		let token = Tokenizer::new("f(a\n [b])").nth(1).unwrap().unwrap();
+		assert_eq!(token.span, span(1, 9, 1, 2));
+		let Token::Parens(inner) = token.token else {
+			panic!("expected parens, got {:?}", token.token);
+		};
+		assert_eq!(inner[0].span, span(2, 3, 1, 3));
+		assert_eq!(inner[1].span, span(5, 8, 2, 2));
+		let Token::Brackets(inner) = &inner[1].token else {
+			panic!("expected brackets, got {:?}", inner[1].token);
+		};
+		assert_eq!(inner[0].span, span(6, 7, 2, 3));
+	}
+
+	#[test]
+	fn errors_know_where_they_are() {
+		let err = error("a\n  (b");
+		assert!(matches!(err.root(), Error::UnclosedBracket(')')));
+		assert_eq!(err.span(), Some(span(4, 5, 2, 3)));
+
+		let err = error("[a\n )");
+		assert!(matches!(
+			err.root(),
+			Error::MismatchedClosingBracket {
+				expected: ']',
+				got: ')'
+			}
+		));
+		assert_eq!(err.span(), Some(span(4, 5, 2, 2)));
+
+		let err = error("a }");
+		assert!(matches!(err.root(), Error::UnexpectedClosingBracket('}')));
+		assert_eq!(err.span(), Some(span(2, 3, 1, 3)));
+
+		let err = error("a\n\"b");
+		assert_eq!(err.span(), Some(span(2, 3, 2, 1)));
+	}
+
+	#[test]
+	fn comments_are_skipped() {
+		let ident = |name: &str| Token::Ident(name.into());
+		assert_eq!(tokens("a // b c\nd // e"), [ident("a"), ident("d")]);
+		assert_eq!(tokens("a /* b */ c"), [ident("a"), ident("c")]);
+		assert_eq!(tokens("a /* b /* c */ d */ e"), [ident("a"), ident("e")]);
+		assert_eq!(tokens("a/**/b"), [ident("a"), ident("b")]);
+		assert_eq!(tokens("/* a\n // b */\nc"), [ident("c")]);
+		assert_eq!(
+			tokens("(a // )\n/* ] */)"),
+			[Token::Parens(vec![SpannedToken {
+				token: ident("a"),
+				span: span(1, 2, 1, 2)
+			}])]
+		);
+
+		// slashes that don't start a comment are still operators
+		assert_eq!(tokens("a / b"), [ident("a"), Token::Slash, ident("b")]);
+		assert_eq!(tokens("a /= b"), [ident("a"), Token::SlashEq, ident("b")]);
+	}
+
+	#[test]
+	fn block_comments_have_to_be_closed() {
+		let err = error("a\n /* b /* c */");
+		assert!(matches!(err.root(), Error::UnclosedComment));
+		assert_eq!(err.span(), Some(span(3, 4, 2, 2)));
+
+		// brackets in comments don't count
+		let err = error("(a /* ) */");
+		assert!(matches!(err.root(), Error::UnclosedBracket(')')));
+	}
+
+	#[test]
+	fn escapes_are_resolved() {
+		let lit = |s: &str| Token::StrLit(s.into());
+		assert_eq!(
+			tokens(r#""a\n\t\r\0 \\ \" \{\}""#),
+			[lit("a\n\t\r\0 \\ \" {}")]
+		);
+		assert_eq!(tokens(r#""\u{e9} \u{1F600}""#), [lit("é 😀")]);
+		// strings can span lines, unless the line break is escaped
+		assert_eq!(tokens("\"a\n  b\""), [lit("a\n  b")]);
+		assert_eq!(tokens("\"a \\\n    b\""), [lit("a b")]);
+	}
+
+	#[test]
+	fn invalid_escapes_are_errors() {
+		let err = error("x\n\"ab\\q\"");
+		assert!(matches!(err.root(), Error::InvalidEscape(escape) if escape == "\\q"));
+		assert_eq!(err.span(), Some(span(5, 7, 2, 4)));
+
+		for (src, escape) in [
+			(r#""\u{zz}""#, r"\u{zz}"),
+			(r#""\u{110000}""#, r"\u{110000}"),
+			(r#""\u{}""#, r"\u{}"),
+			(r#""\u41""#, r"\u"),
+			// the string ends at the quote, before the escape does
+			(r#""\u{41""#, r"\u{41"),
+		] {
+			let err = error(src);
+			assert!(
+				matches!(err.root(), Error::InvalidEscape(got) if got == escape),
+				"{src}: {err}"
+			);
+		}
+		assert!(matches!(error("\"abc").root(), Error::Read(_)));
+	}
+
+	#[test]
+	fn raw_strings_arent_escaped() {
+		let lit = |s: &str| Token::StrLit(s.into());
+		assert_eq!(tokens(r#"r"a\n\u{zz}""#), [lit(r"a\n\u{zz}")]);
+		assert_eq!(tokens(r##"r#"say "hi""#"##), [lit(r#"say "hi""#)]);
+		assert_eq!(tokens(r###"r##"a "# b"##"###), [lit(r##"a "# b"##)]);
+		assert_eq!(tokens("r\"a\nb\" c").len(), 2);
+
+		let token = Tokenizer::new(r##"x r#"a"#"##).nth(1).unwrap().unwrap();
+		assert_eq!(token.span, span(2, 8, 1, 3));
+		// `r` on its own is still a name
+		assert_eq!(tokens("r"), [Token::Ident("r".into())]);
+
+		let err = error(r##"r#"a" b"##);
+		assert_eq!(err.span(), Some(span(0, 3, 1, 1)));
+	}
+
+	#[test]
+	fn format_strings_are_split_into_parts() {
+		let lit = |s: &str| StrPart::Lit(s.into());
+		let ident = |name: &str, span| SpannedToken {
+			token: Token::Ident(name.into()),
+			span,
+		};
+		assert_eq!(
+			tokens(r#"f"a {x} \{b\} {y}""#),
+			[Token::FmtStr(vec![
+				lit("a "),
+				StrPart::Expr(span(4, 5, 1, 5), vec![ident("x", span(5, 6, 1, 6))]),
+				lit(" {b} "),
+				StrPart::Expr(span(14, 15, 1, 15), vec![ident("y", span(15, 16, 1, 16))]),
+			])]
+		);
+		assert_eq!(tokens(r#"f"""#), [Token::FmtStr(vec![])]);
+
+		// the expressions are tokenized like any other code, strings and all
+		let [Token::FmtStr(parts)] = &tokens(r#"f"{ "}" + f"{1}" }""#)[..] else {
+			panic!("expected a single format string");
+		};
+		let [StrPart::Expr(_, inner)] = &parts[..] else {
+			panic!("expected a single expression, got {parts:?}");
+		};
+		assert_eq!(inner[0].token, Token::StrLit("}".into()));
+		assert!(matches!(inner[2].token, Token::FmtStr(_)));
+	}
+
+	#[test]
+	fn format_strings_have_to_be_closed() {
+		let err = error("x f\"a {b}");
+		assert_eq!(err.span(), Some(span(2, 4, 1, 3)));
+		let err = error("f\"a {b\"");
+		assert!(err.span().is_some());
+	}
+
+	#[test]
+	fn operators_are_single_tokens() {
+		use Token::*;
+		let src =
+			"== != >= <= > < && || | ! = => + += - -= * *= ** **= / /= % %= . .. ..= ?. ?? : ,";
+		let expected = [
+			EqEq,
+			NotEq,
+			Ge,
+			Le,
+			Gt,
+			Lt,
+			And,
+			Or,
+			Pipe,
+			Not,
+			Eq,
+			FatArrow,
+			Plus,
+			PlusEq,
+			Minus,
+			MinusEq,
+			Star,
+			StarEq,
+			Pow,
+			PowEq,
+			Slash,
+			SlashEq,
+			Percent,
+			PercentEq,
+			Dot,
+			DotDot,
+			DotDotEq,
+			OptionalDot,
+			Coalesce,
+			Colon,
+			Comma,
+		];
+		assert_eq!(tokens(src), expected);
+
+		let a = || Ident("a".into());
+		let b = || Ident("b".into());
+		assert_eq!(tokens("a!=b"), [a(), NotEq, b()]);
+		assert_eq!(tokens("a&&!b"), [a(), And, Not, b()]);
+		assert_eq!(tokens("a||b"), [a(), Or, b()]);
+		assert_eq!(tokens("|a|"), [Pipe, a(), Pipe]);
+		assert_eq!(tokens("a?.b??a"), [a(), OptionalDot, b(), Coalesce, a()]);
+		assert_eq!(tokens("a>=-b"), [a(), Ge, Minus, b()]);
+		assert_eq!(tokens("a**=b"), [a(), PowEq, b()]);
+
+		let token = Tokenizer::new("a\n  <= b").nth(1).unwrap().unwrap();
+		assert_eq!(token.span, span(4, 6, 2, 3));
+	}
+
+	#[test]
+	fn lone_amps_and_question_marks_are_errors() {
+		let err = error("a &b");
+		assert!(matches!(err.root(), Error::InvalidChar('&')));
+		assert_eq!(err.span(), Some(span(2, 3, 1, 3)));
+		let err = error("a\n?");
+		assert!(matches!(err.root(), Error::InvalidChar('?')));
+		assert_eq!(err.span(), Some(span(2, 3, 2, 1)));
+	}
+
+	#[test]
+	fn number_literals_are_single_tokens() {
+		let num = |s: &str| Token::NumLit(s.into());
+		for lit in [
+			"1",
+			"1.5",
+			"1_000_000",
+			"1e3",
+			"1.5e-3",
+			"2E+10",
+			"0xFF",
+			"0o17",
+			"0b1010",
+		] {
+			assert_eq!(tokens(lit), [num(lit)]);
+		}
+
+		// only decimal numbers have fractions and exponents
+		assert_eq!(tokens("0x1e-3"), [num("0x1e"), Token::Minus, num("3")]);
+		assert_eq!(tokens("0b1.5"), [num("0b1"), Token::Dot, num("5")]);
+		// and only one of each
+		assert_eq!(
+			tokens("1.5.x"),
+			[num("1.5"), Token::Dot, Token::Ident("x".into())]
+		);
+		assert_eq!(tokens("1..3"), [num("1"), Token::DotDot, num("3")]);
+		assert_eq!(
+			tokens("1.max"),
+			[num("1"), Token::Dot, Token::Ident("max".into())]
+		);
+		// indexes after a dot aren't fractions
+		assert_eq!(
+			tokens("a.0.1"),
+			[
+				Token::Ident("a".into()),
+				Token::Dot,
+				num("0"),
+				Token::Dot,
+				num("1")
+			]
+		);
+		// the sign is a token of its own, the parser puts it back together
+		assert_eq!(tokens("-1"), [Token::Minus, num("1")]);
+
+		let token = Tokenizer::new("x 1.5e-3").nth(1).unwrap().unwrap();
+		assert_eq!(token.span, span(2, 8, 1, 3));
+	}
+}
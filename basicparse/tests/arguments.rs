@@ -0,0 +1,76 @@
+mod common;
+
+use common::{eval, fail, parse};
+use interpret::{Error, IValue};
+
+fn string(s: &str) -> IValue {
+	IValue::String(s.into())
+}
+
+#[test]
+fn defaults_fill_in_missing_args() {
+	let open = "let open = fn(path, mode = \"r\") { f\"{path} {mode}\" } ";
+	assert_eq!(eval(&format!("{open} open(\"a\")")), string("a r"));
+	assert_eq!(eval(&format!("{open} open(\"a\", \"w\")")), string("a w"));
+	assert!(matches!(
+		fail(&format!("{open} open()")),
+		Error::MissingArg(name) if name == "path"
+	));
+}
+
+#[test]
+fn defaults_are_evaluated_on_every_call_and_see_earlier_params() {
+	let src = "
+		let calls = 0
+		let count = fn() { calls += 1 calls }
+		let f = fn(a, b = a * 2, c = count()) { [a b c] }
+		[f(1) f(1, 5) f(1)]
+	";
+	let arr = |a, b, c| IValue::Array(vec![IValue::i32(a), IValue::i32(b), IValue::i32(c)]);
+	assert_eq!(
+		eval(src),
+		IValue::Array(vec![arr(1, 2, 1), arr(1, 5, 2), arr(1, 2, 3)])
+	);
+}
+
+#[test]
+fn named_args() {
+	let open = "let open = fn(path, mode = \"r\", create = 0) { f\"{path} {mode} {create}\" } ";
+	assert_eq!(
+		eval(&format!("{open} open(path: \"a\", mode: \"w\")")),
+		string("a w 0")
+	);
+	assert_eq!(
+		eval(&format!("{open} open(\"a\", create: 1)")),
+		string("a r 1")
+	);
+	assert_eq!(
+		eval(&format!("{open} open(create: 1, path: \"a\")")),
+		string("a r 1")
+	);
+	assert_eq!(
+		eval("let f = |x, y = 1| x - y f(y: 3, x: 10)"),
+		IValue::i32(7)
+	);
+}
+
+#[test]
+fn named_arg_errors() {
+	let open = "let open = fn(path, mode = \"r\") { path } ";
+	assert!(matches!(
+		fail(&format!("{open} open(\"a\", mdoe: \"w\")")),
+		Error::UnknownArg { name, .. } if name == "mdoe"
+	));
+	assert!(matches!(
+		fail(&format!("{open} open(\"a\", path: \"b\")")),
+		Error::ArgPassedTwice(name) if name == "path"
+	));
+
+	let err = parse(&format!("{open} open(path: \"a\", path: \"b\")")).unwrap_err();
+	assert!(matches!(err.root(), basicparse::Error::DuplicateNamedArg(name) if name == "path"));
+	let err = parse(&format!("{open} open(path: \"a\", \"r\")")).unwrap_err();
+	assert!(matches!(
+		err.root(),
+		basicparse::Error::PositionalAfterNamed
+	));
+}
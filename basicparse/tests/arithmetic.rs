@@ -0,0 +1,82 @@
+mod common;
+
+use common::{eval, fail};
+use interpret::{Error, IValue};
+
+#[test]
+fn integer_arithmetic() {
+	assert_eq!(eval("6 * 7"), IValue::i32(42));
+	assert_eq!(eval("7 / 2"), IValue::i32(3));
+	assert_eq!(eval("-7 / 2"), IValue::i32(-3));
+	assert_eq!(eval("-7 % 3"), IValue::i32(-1));
+	assert_eq!(eval("2 ** 10"), IValue::i32(1024));
+	assert_eq!(eval("5 ** 0"), IValue::i32(1));
+}
+
+#[test]
+fn ints_get_promoted_to_floats() {
+	assert_eq!(eval("2 * 1.5"), IValue::f32(3.0));
+	assert_eq!(eval("1.5 * 2"), IValue::f32(3.0));
+	assert_eq!(eval("7 / 2.0"), IValue::f32(3.5));
+	assert_eq!(eval("7.5 % 2"), IValue::f32(1.5));
+	assert_eq!(eval("4 ** 0.5"), IValue::f32(2.0));
+	assert_eq!(eval("2 ** -1"), IValue::f32(0.5));
+}
+
+#[test]
+fn division_by_zero_is_an_error() {
+	assert!(matches!(
+		fail("1 / 0"),
+		Error::DivisionByZero { op: "/", .. }
+	));
+	assert!(matches!(
+		fail("1 % 0"),
+		Error::DivisionByZero { op: "%", .. }
+	));
+	assert!(matches!(fail("1.5 / 0.0"), Error::DivisionByZero { .. }));
+}
+
+#[test]
+fn integer_overflow_is_an_error() {
+	assert!(matches!(
+		fail("2147483647 + 1"),
+		Error::Overflow { op: "+", .. }
+	));
+	assert!(matches!(
+		fail("-2147483648 - 1"),
+		Error::Overflow { op: "-", .. }
+	));
+	assert!(matches!(
+		fail("65536 * 65536"),
+		Error::Overflow { op: "*", .. }
+	));
+	assert!(matches!(fail("2 ** 31"), Error::Overflow { op: "**", .. }));
+	assert!(matches!(
+		fail("-2147483648 / -1"),
+		Error::Overflow { op: "/", .. }
+	));
+}
+
+#[test]
+fn invalid_operands_are_an_error() {
+	assert!(matches!(
+		fail("\"a\" * 2"),
+		Error::InvalidMultiplication { .. }
+	));
+	assert!(matches!(fail("[1] / 2"), Error::InvalidDivision { .. }));
+}
+
+#[test]
+fn negation() {
+	assert_eq!(eval("let x = 5 let y = -x y"), IValue::i32(-5));
+	assert_eq!(eval("let x = 1.5 let y = -x y"), IValue::f32(-1.5));
+	assert_eq!(
+		eval("let x = -2147483647 let y = -x y"),
+		IValue::i32(2147483647)
+	);
+	assert!(matches!(
+		fail("let x = -2147483648 let y = -x"),
+		Error::NegOverflow(_)
+	));
+	assert!(matches!(fail("-\"a\""), Error::InvalidNegation(_)));
+}
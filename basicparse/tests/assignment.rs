@@ -0,0 +1,108 @@
+mod common;
+
+use common::{eval, fail, parse};
+use interpret::{Error, IValue};
+
+#[test]
+fn assign_to_fields_and_elements() {
+	assert_eq!(eval("let a = [1 2 3] a.1 = 5 a"), eval("[1 5 3]"));
+	assert_eq!(
+		eval("let a = [1 2 3] let i = 2 a.[i] = 5 a"),
+		eval("[1 2 5]")
+	);
+	assert_eq!(
+		eval("let p = obj { name: \"bob\" } p.name = \"tim\" p.age = 3 [p.name p.age]"),
+		eval("[\"tim\" 3]")
+	);
+}
+
+#[test]
+fn assign_to_nested_index_chains() {
+	let src = "
+		let a = obj { b: [obj { c: 1 } obj { c: 2 }] }
+		let i = 1
+		a.b.[i].c = 20
+		a.b.0.c = 10
+		[a.b.0.c a.b.1.c]
+	";
+	assert_eq!(eval(src), eval("[10 20]"));
+}
+
+#[test]
+fn assignment_changes_only_the_variable() {
+	let src = "
+		let a = [1 2]
+		let b = a
+		a.0 = 5
+		[a b]
+	";
+	assert_eq!(eval(src), eval("[[5 2] [1 2]]"));
+}
+
+#[test]
+fn assignment_from_inside_a_closure() {
+	let src = "
+		let counts = obj { calls: 0 }
+		let f = fn() { counts.calls += 1 }
+		f()
+		f()
+		counts.calls
+	";
+	assert_eq!(eval(src), IValue::i32(2));
+}
+
+#[test]
+fn compound_assignment() {
+	let src = "
+		let a = 10
+		a += 5
+		a -= 3
+		a *= 2
+		a /= 4
+		a %= 4
+		let b = 2
+		b **= 3
+		[a b]
+	";
+	assert_eq!(eval(src), eval("[2 8]"));
+	assert_eq!(eval("let a = [[1]] a.0 += [2] a"), eval("[[1 2]]"));
+}
+
+#[test]
+fn compound_assignment_evaluates_the_path_once() {
+	// `f` moves `i` along every time it's called, so the old value has to come from the same
+	// element the new one goes into
+	let src = "let a = [1 2] let i = 0 let f = fn() { i += 1 i - 1 } a.[f()] += 10 [a i]";
+	assert_eq!(
+		eval(src),
+		IValue::Array(vec![
+			IValue::Array(vec![IValue::i32(11), IValue::i32(2)]),
+			IValue::i32(1)
+		])
+	);
+	let src =
+		"let o = obj { x: [5] } let n = 0 let key = fn() { n += 1 \"x\" } o.[key()].0 *= 2 [o.x n]";
+	assert_eq!(
+		eval(src),
+		IValue::Array(vec![IValue::Array(vec![IValue::i32(10)]), IValue::i32(1)])
+	);
+}
+
+#[test]
+fn invalid_assignments() {
+	assert!(matches!(
+		fail("let a = [1 2] a.2 = 3"),
+		Error::InvalidIndexAssign { .. }
+	));
+	assert!(matches!(
+		fail("let a = obj {} a.b.c = 3"),
+		Error::InvalidIndexAssign { .. }
+	));
+	assert!(matches!(
+		fail("let a = 5 a.b = 3"),
+		Error::InvalidIndexAssign { .. }
+	));
+
+	let err = parse("1 + 2 = 3").expect_err("expected an error");
+	assert!(err.to_string().contains("can be assigned to"), "{err}");
+}
@@ -0,0 +1,16 @@
+mod common;
+
+use common::eval;
+use interpret::IValue;
+
+#[test]
+fn comments_dont_become_values() {
+	let src = "
+		// the last statement of a block is what it returns, comments aren't statements
+		let f = fn() {
+			1 /* nested /* block */ comment */
+			// line comment
+		}
+		f()";
+	assert_eq!(eval(src), IValue::i32(1));
+}
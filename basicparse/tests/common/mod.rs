@@ -0,0 +1,32 @@
+//! helpers shared between the test files, which pull them in with `mod common;`
+// every test file is its own crate, and none of them uses all of these
+#![allow(dead_code)]
+
+use basicparse::{Parser, preproc};
+use interpret::{Context, Error, IValue};
+use langlib::Statement;
+
+/// parses `src` into statements, running them through [preproc] like the cli does
+pub fn parse(src: &str) -> basicparse::Result<Vec<Statement>> {
+	preproc(Parser::new(src).statements()).collect()
+}
+/// parses and runs `src`, panicking if either of them fails
+pub fn eval(src: &str) -> IValue {
+	let parsed = parse(src).expect("failed to parse");
+	Context::default().exec(parsed).expect("failed to run")
+}
+/// the error running `src` fails with, without the location info
+pub fn fail(src: &str) -> Error {
+	let parsed = parse(src).expect("failed to parse");
+	match Context::default().exec(parsed) {
+		Ok(val) => panic!("expected an error, got {val}"),
+		Err(err) => unlocated(err),
+	}
+}
+/// the error without the location info
+pub fn unlocated(err: Error) -> Error {
+	match err {
+		Error::At { err, .. } => *err,
+		err => err,
+	}
+}
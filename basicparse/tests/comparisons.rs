@@ -0,0 +1,37 @@
+mod common;
+
+use basicparse::Error;
+use common::{eval, parse};
+use interpret::IValue;
+
+#[test]
+fn comparisons_compare() {
+	for (src, expected) in [
+		("1 == 1", true),
+		("1 != 1", false),
+		("2 > 1", true),
+		("2 < 1", false),
+		("2 >= 2", true),
+		("2 <= 1", false),
+		("\"a\" == \"a\"", true),
+		("\"a\" != \"b\"", true),
+	] {
+		assert_eq!(eval(src), IValue::bool(expected), "{src}");
+	}
+}
+
+#[test]
+fn operators_dont_need_whitespace() {
+	assert_eq!(
+		eval("let a = 1 let b = 2 a!=b&&!(a>=b)"),
+		IValue::bool(true)
+	);
+	assert_eq!(eval("let a = 1 a==2||a<=1"), IValue::bool(true));
+}
+
+#[test]
+fn single_amps_arent_operators() {
+	let err = parse("let a = 1\na & a").unwrap_err();
+	assert!(matches!(err.root(), Error::InvalidChar('&')));
+	assert_eq!(err.span().map(|span| (span.line, span.col)), Some((2, 3)));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::{eval, fail, parse};
+use interpret::{Context, Error, IValue};
+
+fn modified_const(err: Error, name: &str) -> bool {
+	matches!(err, Error::ModifiedConst(n) if n == name)
+}
+
+#[test]
+fn consts_can_be_read() {
+	assert_eq!(eval("const x = 5 x + 1"), IValue::i32(6));
+	assert_eq!(eval("const [a b] = [1 2] a + b"), IValue::i32(3));
+}
+
+#[test]
+fn consts_cant_be_changed() {
+	assert!(modified_const(fail("const x = 5 x = 6"), "x"));
+	assert!(modified_const(fail("const x = 5 x += 1"), "x"));
+	assert!(modified_const(fail("const [a b] = [1 2] b = 3"), "b"));
+	assert!(modified_const(
+		fail("const lib = obj { f: 1 } lib.f = 2"),
+		"lib"
+	));
+	// not even from inside a function
+	assert!(modified_const(
+		fail("const x = 5 let f = fn() { x = 6 } f()"),
+		"x"
+	));
+	// or through `self`, calling methods that don't change it is fine though
+	let src = "
+		const counter = obj { c: 0 get: fn() { self.c } inc: fn() { self.c += 1 } }
+		counter.get()
+		counter.inc()
+	";
+	assert!(modified_const(fail(src), "counter"));
+}
+
+#[test]
+fn consts_can_be_shadowed() {
+	assert_eq!(eval("const x = 5 let x = 6 x = 7 x"), IValue::i32(7));
+	assert_eq!(
+		eval("const x = 5 let f = fn() { let x = 1 x += 1 x } f() + x"),
+		IValue::i32(7)
+	);
+	assert!(modified_const(fail("let x = 5 const x = 6 x = 7"), "x"));
+}
+
+#[test]
+fn builtins_are_const() {
+	let mut ctx = Context::default();
+	ctx.builtins([]);
+	let parsed = parse("builtins = 5").unwrap();
+	let err = match ctx.exec(parsed).unwrap_err() {
+		Error::At { err, .. } => *err,
+		err => err,
+	};
+	assert!(modified_const(err, "builtins"));
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use common::{eval, parse};
+use interpret::{Context, Error, IValue};
+use langlib::{Expr, Reach, Statement, Value};
+
+fn parse_err(src: &str) -> String {
+	parse(src).expect_err("expected an error").to_string()
+}
+
+#[test]
+fn return_leaves_the_function_early() {
+	let src = "
+		let f = fn(x) {
+			return x * 2
+			x
+		}
+		f(3)
+	";
+	assert_eq!(eval(src), IValue::i32(6));
+}
+
+#[test]
+fn return_from_nested_blocks_and_loops() {
+	let src = "
+		let find = fn(arr, needle) {
+			let i = 0
+			for x in arr {
+				loop {
+					if x == needle {
+						return i
+					}
+					break
+				}
+				i = i + 1
+			}
+			-1
+		}
+		[find([4 5 6], 6) find([4 5 6], 7)]
+	";
+	assert_eq!(
+		eval(src),
+		IValue::Array(vec![IValue::i32(2), IValue::i32(-1)])
+	);
+}
+
+#[test]
+fn bare_return_returns_none() {
+	let src = "
+		let f = fn() {
+			if 1 == 1 { return }
+			5
+		}
+		f()
+	";
+	assert_eq!(eval(src), IValue::None());
+	assert_eq!(
+		eval("let f = fn() { return let x = 1 } f()"),
+		IValue::None()
+	);
+}
+
+#[test]
+fn return_only_leaves_the_innermost_function() {
+	let src = "
+		let inner = fn() { return 1 2 }
+		let outer = fn() {
+			let a = inner()
+			a + 10
+		}
+		outer()
+	";
+	assert_eq!(eval(src), IValue::i32(11));
+}
+
+#[test]
+fn break_and_continue() {
+	let src = "
+		let sum = 0
+		let i = 0
+		loop {
+			i = i + 1
+			if i > 10 { break }
+			if i % 2 == 0 { continue }
+			sum = sum + i
+		}
+		sum
+	";
+	assert_eq!(eval(src), IValue::i32(25));
+}
+
+#[test]
+fn break_and_continue_in_for_loops() {
+	let src = "
+		let out = []
+		for x in [1 2 3 4 5] {
+			if x == 2 { continue }
+			if x == 4 { break }
+			out = out + [x]
+		}
+		out
+	";
+	assert_eq!(
+		eval(src),
+		IValue::Array(vec![IValue::i32(1), IValue::i32(3)])
+	);
+}
+
+#[test]
+fn labeled_loops() {
+	let src = "
+		let pairs = []
+		'outer: for a in [1 2 3] {
+			for b in [1 2 3] {
+				if b > a { continue 'outer }
+				if a == 3 { break 'outer }
+				pairs = pairs + [[a b]]
+			}
+		}
+		pairs
+	";
+	let pair = |a, b| IValue::Array(vec![IValue::i32(a), IValue::i32(b)]);
+	assert_eq!(
+		eval(src),
+		IValue::Array(vec![pair(1, 1), pair(2, 1), pair(2, 2)])
+	);
+	assert_eq!(
+		eval("let n = 0 'a: loop { loop { n = n + 1 break 'a } } n"),
+		IValue::i32(1)
+	);
+}
+
+#[test]
+fn stray_break_is_a_parse_error() {
+	assert!(parse_err("break").contains("`break` can only be used inside of a loop"));
+	assert!(parse_err("if 1 == 1 { continue }").contains("`continue` can only be used"));
+	// a function body doesn't know which loop it's going to get called from
+	assert!(parse_err("loop { let f = fn() { break } }").contains("`break` can only be used"));
+	assert!(parse_err("'a: loop { break 'b }").contains("no loop labeled 'b"));
+	assert!(parse_err("'a: 5").contains("expected a loop after label 'a:"));
+}
+
+#[test]
+fn return_outside_of_a_function() {
+	assert!(parse_err("return 5").contains("`return` can only be used inside of a function"));
+	assert!(parse_err("loop { if 1 == 1 { return } }").contains("`return` can only be used"));
+	assert!(
+		parse_err("let a = if 1 == 1 { return 2 } else { 3 }")
+			.contains("`return` can only be used")
+	);
+	// lambdas are functions too
+	assert_eq!(eval("let f = |x| { return x } f(1)"), IValue::i32(1));
+
+	// statements that didn't come from the parser can still have one
+	let stmt = Statement::Return(Expr::Reach(Reach::Value(Value::i32(5))));
+	let err = Context::default()
+		.exec(vec![stmt])
+		.expect_err("expected an error");
+	assert!(matches!(err, Error::Uncaught(_)));
+}
+
+#[test]
+fn while_loops_until_the_condition_is_false() {
+	let src = "
+		let i = 0
+		let sum = 0
+		while i < 5 {
+			i = i + 1
+			if i == 2 { continue }
+			sum = sum + i
+		}
+		sum
+	";
+	assert_eq!(eval(src), IValue::i32(13));
+}
+
+#[test]
+fn break_out_of_labeled_while() {
+	let src = "
+		let n = 0
+		'outer: while 1 == 1 {
+			while n < 10 {
+				n = n + 1
+				if n == 3 { break 'outer }
+			}
+		}
+		n
+	";
+	assert_eq!(eval(src), IValue::i32(3));
+}
+
+#[test]
+fn else_if_chains() {
+	let src = "
+		let sign = fn(x) {
+			if x < 0 { \"negative\" } else if x == 0 { \"zero\" } else { \"positive\" }
+		}
+		[sign(0 - 4) sign(0) sign(7)]
+	";
+	assert_eq!(eval(src), eval("[\"negative\" \"zero\" \"positive\"]"));
+	assert_eq!(eval("if 1 == 2 { 1 } else if 2 == 3 { 2 }"), IValue::None());
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::{eval, fail, parse};
+use interpret::{Error, IValue};
+
+#[test]
+fn let_array() {
+	assert_eq!(eval("let [a b] = [1 2] a - b"), IValue::i32(-1));
+	assert_eq!(eval("let [_ [x y]] = [1 [2 3]] x * y"), IValue::i32(6));
+}
+
+#[test]
+fn let_object() {
+	let src = "
+		let person = obj { name: \"bob\" age: 30 pet: obj { name: \"rex\" } }
+		let obj { name age pet: obj { name: pet_name } } = person
+		f\"{name} {age} {pet_name}\"
+	";
+	assert_eq!(eval(src), IValue::String("bob 30 rex".into()));
+}
+
+#[test]
+fn rest_patterns() {
+	assert_eq!(
+		eval("let [head ..tail] = [1 2 3] [head tail]"),
+		eval("[1 [2 3]]")
+	);
+	assert_eq!(
+		eval("let [first ..middle last] = [1 2 3 4] [first middle last]"),
+		eval("[1 [2 3] 4]")
+	);
+	assert_eq!(eval("let [a ..rest] = [1] rest"), eval("[]"));
+	assert_eq!(eval("let [.. last] = [1 2 3] last"), IValue::i32(3));
+}
+
+#[test]
+fn for_loop_patterns() {
+	let src = "
+		let sum = 0
+		for [k v] in [[1 2] [3 4]] {
+			sum = sum + k * v
+		}
+		sum
+	";
+	assert_eq!(eval(src), IValue::i32(14));
+
+	let src = "
+		let i = 0
+		let pairs = obj {
+			next: fn() {
+				i = i + 1
+				if i < 3 { obj { n: i } }
+			}
+		}
+		let out = []
+		for obj { n } in pairs {
+			out = out + [n]
+		}
+		out
+	";
+	assert_eq!(eval(src), eval("[1 2]"));
+}
+
+#[test]
+fn mismatched_patterns_are_errors() {
+	assert!(matches!(
+		fail("let [a b] = [1 2 3]"),
+		Error::PatternMismatch { .. }
+	));
+	assert!(matches!(
+		fail("let obj { name } = obj { age: 1 }"),
+		Error::PatternMismatch { .. }
+	));
+	assert!(matches!(
+		fail("for [a b] in [[1 2] 3] {}"),
+		Error::PatternMismatch { .. }
+	));
+}
+
+#[test]
+fn only_one_rest() {
+	let err = parse("let [..a ..b] = [1 2]").expect_err("expected an error");
+	assert!(err.to_string().contains("only have one `..`"), "{err}");
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::{eval, fail, parse};
+use interpret::{Error, IValue};
+
+#[test]
+fn multiple_params() {
+	assert_eq!(
+		eval("let f = fn(a, b, c) { a - b - c } f(10, 3, 2)"),
+		IValue::i32(5)
+	);
+	assert_eq!(eval("let f = fn() { 1 } f()"), IValue::i32(1));
+	// trailing commas are fine
+	assert_eq!(eval("let f = fn(a, b,) { a - b } f(3, 1,)"), IValue::i32(2));
+}
+
+#[test]
+fn args_are_full_expressions() {
+	assert_eq!(
+		eval("let f = fn(a, b) { [a b] } f(1 + 2, [3 4].1)"),
+		IValue::Array(vec![IValue::i32(3), IValue::i32(4)])
+	);
+}
+
+#[test]
+fn arity_errors() {
+	assert!(matches!(fail("let f = fn(a, b) { a } f(1)"), Error::MissingArg(name) if name == "b"));
+	assert!(matches!(
+		fail("let f = fn(a) { a } f(1, 2)"),
+		Error::WrongArgCount {
+			expected: 1,
+			got: 2,
+			..
+		}
+	));
+}
+
+#[test]
+fn invalid_declarations() {
+	let err = |src| parse(src).expect_err("expected an error").to_string();
+	assert!(err("fn(a, a) { a }").contains("more than one argument called `a`"));
+	assert!(err("fn(a b) { a }").contains("expected comma"));
+	assert!(err("let f = fn(a) { a } f(1 2)").contains("expected comma"));
+}
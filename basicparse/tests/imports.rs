@@ -0,0 +1,142 @@
+mod common;
+
+use std::fs;
+
+use common::{parse, unlocated};
+use interpret::{Context, Error, IValue, Loader};
+
+/// writes the files into a fresh folder (named after the test), then runs `main.dl` from it \
+/// the error is boxed, it's too big to pass around as is
+fn run(test: &str, files: &[(&str, &str)]) -> Result<IValue, Box<Error>> {
+	let dir = std::env::temp_dir().join(format!("dynlang-{test}-{}", std::process::id()));
+	let _ = fs::remove_dir_all(&dir);
+	for (name, src) in files {
+		let path = dir.join(name);
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(path, src).unwrap();
+	}
+
+	let main = dir.join("main.dl");
+	let parsed = parse(&fs::read_to_string(&main).unwrap()).expect("failed to parse");
+	let mut ctx = Context::default();
+	ctx.imports(
+		Loader::new(|src| parse(src).map_err(|err| err.to_string()), []),
+		Some(&main),
+	);
+	let out = ctx.exec(parsed).map_err(Box::new);
+	fs::remove_dir_all(&dir).unwrap();
+	out
+}
+fn eval(test: &str, files: &[(&str, &str)]) -> IValue {
+	run(test, files).expect("failed to run")
+}
+/// the error without the location info
+fn fail(test: &str, files: &[(&str, &str)]) -> Error {
+	unlocated(*run(test, files).expect_err("expected an error"))
+}
+
+#[test]
+fn modules_are_imported_as_objects() {
+	let files = [
+		("main.dl", r#"import "lib.dl" as lib lib.double(lib.x)"#),
+		("lib.dl", "let x = 4 let double = fn(n) { n * 2 }"),
+	];
+	assert_eq!(eval("as", &files), IValue::i32(8));
+}
+
+#[test]
+fn selected_bindings_can_be_imported() {
+	let files = [
+		("main.dl", r#"import { a b } from "lib.dl" a + b"#),
+		("lib.dl", "let a = 1 let b = 2 let c = 3"),
+	];
+	assert_eq!(eval("selected", &files), IValue::i32(3));
+
+	let files = [
+		("main.dl", r#"import { a d } from "lib.dl" a"#),
+		("lib.dl", "let a = 1"),
+	];
+	assert!(matches!(fail("not_exported", &files), Error::NotExported { name, .. } if name == "d"));
+}
+
+#[test]
+fn paths_are_relative_to_the_importing_file() {
+	let files = [
+		("main.dl", r#"import { x } from "sub/a.dl" x"#),
+		("sub/a.dl", r#"import { y } from "b.dl" let x = y + 1"#),
+		("sub/b.dl", "let y = 1"),
+	];
+	assert_eq!(eval("relative", &files), IValue::i32(2));
+}
+
+#[test]
+fn modules_cant_see_the_importers_variables() {
+	let files = [
+		("main.dl", r#"let secret = 1 import "lib.dl" as lib"#),
+		("lib.dl", "let x = secret"),
+	];
+	assert!(matches!(fail("isolated", &files), Error::InModule { .. }));
+}
+
+#[test]
+fn modules_only_run_once() {
+	// if counter.dl ran twice, `b` would get a counter of its own
+	let files = [
+		(
+			"main.dl",
+			r#"import "counter.dl" as c import "b.dl" as b c.bump() b.bump_too()"#,
+		),
+		(
+			"b.dl",
+			r#"import { bump } from "counter.dl" let bump_too = fn() { bump() }"#,
+		),
+		("counter.dl", "let n = 0 let bump = fn() { n += 1 n }"),
+	];
+	assert_eq!(eval("once", &files), IValue::i32(2));
+}
+
+#[test]
+fn import_cycles_are_errors() {
+	let files = [
+		("main.dl", r#"import "a.dl" as a"#),
+		("a.dl", r#"import "b.dl" as b"#),
+		("b.dl", r#"import "a.dl" as a"#),
+	];
+	let Error::InModule { err, .. } = fail("cycle", &files) else {
+		panic!("expected the error to come from a.dl");
+	};
+	let Error::At { err, .. } = *err else {
+		panic!("expected the error to have a location");
+	};
+	assert!(matches!(*err, Error::InModule { .. }));
+
+	let files = [("main.dl", r#"import "main.dl" as me"#)];
+	assert!(matches!(fail("self_import", &files), Error::ImportCycle(cycle) if cycle.len() == 2));
+}
+
+#[test]
+fn imports_are_consts() {
+	let files = [
+		("main.dl", r#"import { x } from "lib.dl" x = 2"#),
+		("lib.dl", "let x = 1"),
+	];
+	assert!(matches!(fail("const", &files), Error::ModifiedConst(name) if name == "x"));
+}
+
+#[test]
+fn import_needs_a_loader() {
+	let parsed = parse(r#"import "lib.dl" as lib"#).unwrap();
+	assert!(matches!(
+		Context::default().exec(parsed),
+		Err(Error::At { err, .. }) if matches!(*err, Error::ImportsUnavailable)
+	));
+}
+
+#[test]
+fn invalid_imports_dont_parse() {
+	assert!(parse(r#"import "lib.dl""#).is_err());
+	assert!(parse(r#"import "lib.dl" from lib"#).is_err());
+	assert!(parse(r#"import { a a } from "lib.dl""#).is_err());
+	assert!(parse(r#"import { a } "lib.dl""#).is_err());
+	assert!(parse("import lib").is_err());
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use basicparse::{Error, Parser};
+use common::{eval, parse};
+use interpret::IValue;
+use langlib::{Block, Expr, Function, Param, Reach, Statement, Value};
+
+#[test]
+fn lambdas_are_functions() {
+	let lambda = Parser::new("|a, b| a").read_expr().unwrap();
+	let Expr::Reach(Reach::Value(Value::Function(Function { params, block }))) =
+		lambda.into_unspanned()
+	else {
+		panic!("expected a function");
+	};
+	assert_eq!(params, [Param::new("a".into()), Param::new("b".into())]);
+	let Block(body) = block;
+	assert!(matches!(&body[..], [Statement::Expr(_)]));
+}
+
+#[test]
+fn lambda_bodies_take_the_whole_expr() {
+	assert_eq!(eval("let f = |x| x * 2 + 1 f(3)"), IValue::i32(7));
+	assert_eq!(eval("let f = |a, b| a - b f(5, 2)"), IValue::i32(3));
+	assert_eq!(eval("let f = || 42 f()"), IValue::i32(42));
+	assert_eq!(
+		eval("let f = |x| { let y = x + 1 y * y } f(2)"),
+		IValue::i32(9)
+	);
+}
+
+#[test]
+fn lambdas_as_args_and_closures() {
+	let src = "
+		let apply = fn(f, x) { f(x) }
+		let add = |a| |b| a + b
+		apply(|x| x * 10, add(1)(2))
+	";
+	assert_eq!(eval(src), IValue::i32(30));
+
+	let src = "
+		let n = 0
+		let bump = || { n += 1 }
+		bump() bump()
+		n
+	";
+	assert_eq!(eval(src), IValue::i32(2));
+}
+
+#[test]
+fn unclosed_lambda_params() {
+	assert!(matches!(
+		parse("let f = |x x").unwrap_err().root(),
+		Error::ExpectedLambdaPipe
+	));
+	assert!(matches!(
+		parse("let f = |x y| x").unwrap_err().root(),
+		Error::ExpectedCommaGot(_)
+	));
+}
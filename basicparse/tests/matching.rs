@@ -0,0 +1,106 @@
+mod common;
+
+use common::{eval, fail, parse};
+use interpret::{Error, IValue};
+
+#[test]
+fn literal_patterns() {
+	let src = "
+		let name = fn(n) {
+			match n {
+				1 => \"one\",
+				-1 => \"minus one\",
+				\"two\" => 2,
+				None => \"nothing\",
+				_ => \"something else\",
+			}
+		}
+		[name(1) name(0 - 1) name(\"two\") name(if 1 == 2 {}) name(5)]
+	";
+	assert_eq!(
+		eval(src),
+		eval("[\"one\" \"minus one\" 2 \"nothing\" \"something else\"]")
+	);
+}
+
+#[test]
+fn bindings_and_guards() {
+	let src = "
+		let classify = fn(n) {
+			match n {
+				x if x < 0 => \"negative\",
+				0 => \"zero\",
+				x => f\"positive {x}\",
+			}
+		}
+		[classify(0 - 3) classify(0) classify(3)]
+	";
+	assert_eq!(eval(src), eval("[\"negative\" \"zero\" \"positive 3\"]"));
+}
+
+#[test]
+fn array_patterns() {
+	let src = "
+		let sum = fn(arr) {
+			match arr {
+				[] => 0,
+				[a] => a,
+				[a [b c]] => a + b + c,
+				[a b] => a + b,
+				_ => 100,
+			}
+		}
+		[sum([]) sum([1]) sum([1 2]) sum([1 [2 3]]) sum([1 2 3])]
+	";
+	assert_eq!(eval(src), eval("[0 1 3 6 100]"));
+}
+
+#[test]
+fn object_patterns() {
+	let src = "
+		let describe = fn(p) {
+			match p {
+				obj { kind: \"dog\" name } => f\"{name} the dog\",
+				obj { name age: a } if a > 20 => {
+					let title = \"old\"
+					f\"{title} {name}\"
+				}
+				obj { name } => name,
+			}
+		}
+		[
+			describe(obj { kind: \"dog\" name: \"rex\" })
+			describe(obj { name: \"bob\" age: 30 })
+			describe(obj { name: \"tim\" age: 3 })
+		]
+	";
+	assert_eq!(eval(src), eval("[\"rex the dog\" \"old bob\" \"tim\"]"));
+}
+
+#[test]
+fn bindings_dont_leak_out_of_the_arm() {
+	let src = "
+		let x = 1
+		let y = match [5] { [x] => x }
+		[x y]
+	";
+	assert_eq!(eval(src), eval("[1 5]"));
+}
+
+#[test]
+fn no_matching_arm_is_an_error() {
+	assert!(matches!(
+		fail("match 3 { 1 => 1, 2 => 2 }"),
+		Error::NoMatchingArm(val) if val == IValue::i32(3)
+	));
+	assert!(matches!(
+		fail("match [1 2] { [a] => a, x if x == 1 => x }"),
+		Error::NoMatchingArm(_)
+	));
+}
+
+#[test]
+fn arms_need_a_fat_arrow() {
+	let err = parse("match 3 { 1 2 }").expect_err("expected an error");
+	assert!(err.to_string().contains("expected `=>`"), "{err}");
+}
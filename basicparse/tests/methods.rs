@@ -0,0 +1,122 @@
+mod common;
+
+use common::{eval, fail};
+use interpret::{Error, IValue};
+
+#[test]
+fn methods_see_the_object_as_self() {
+	let src = "
+		let dog = obj {
+			name: \"rex\"
+			greet: fn(greeting) { f\"{greeting}, {self.name}\" }
+		}
+		dog.greet(\"hi\")
+	";
+	assert_eq!(eval(src), IValue::String("hi, rex".into()));
+}
+
+#[test]
+fn self_is_whatever_the_method_was_taken_from() {
+	let src = "
+		let name = fn() { self.name }
+		let a = obj { name: \"a\" f: name }
+		let b = obj { name: \"b\" f: name }
+		let nested = obj { inner: b }
+		[a.f() b.f() nested.inner.f() nested.[\"inner\"].f()]
+	";
+	assert_eq!(
+		eval(src),
+		IValue::Array(
+			["a", "b", "b", "b"]
+				.map(|s| IValue::String(s.into()))
+				.into()
+		)
+	);
+}
+
+#[test]
+fn methods_can_call_other_methods() {
+	let src = "
+		let shape = obj {
+			w: 3
+			h: 4
+			area: fn() { self.w * self.h }
+			describe: fn() { f\"area {self.area()}\" }
+		}
+		shape.describe()
+	";
+	assert_eq!(eval(src), IValue::String("area 12".into()));
+}
+
+#[test]
+fn plain_calls_dont_get_a_self() {
+	let src = "
+		let f = fn() { self }
+		f()
+	";
+	assert!(matches!(
+		fail(src),
+		Error::VariableDoesntExist(name, _) if name == "self"
+	));
+}
+
+#[test]
+fn for_loops_call_next_as_a_method() {
+	// the iterator keeps its state in `self`, so the loop has to hold on to the changed version
+	let src = "
+		let iter = obj {
+			count: 0
+			limit: 3
+			next: fn() {
+				if self.count < self.limit {
+					self.count += 1
+					self.count
+				}
+			}
+		}
+		let sum = 0
+		for x in iter {
+			sum += x
+		}
+		sum
+	";
+	assert_eq!(eval(src), IValue::i32(6));
+}
+
+#[test]
+fn changes_to_self_stick() {
+	let src = "
+		let counter = obj { c: 0 inc: fn() { self.c += 1 self.c } }
+		counter.inc()
+		counter.inc()
+		counter.c
+	";
+	assert_eq!(eval(src), IValue::i32(2));
+
+	let src = "
+		let counter = obj { c: 0 inc: fn() { self.c += 1 } }
+		let outer = obj { inner: counter bump: fn() { self.inner.inc() } }
+		outer.bump()
+		outer.inner.inc()
+		let arr = [counter]
+		arr.0.inc()
+		[outer.inner.c arr.0.c counter.c]
+	";
+	assert_eq!(
+		eval(src),
+		IValue::Array(vec![IValue::i32(2), IValue::i32(1), IValue::i32(0)])
+	);
+}
+
+#[test]
+fn changes_to_temporaries_go_away_with_them() {
+	// what `make()` returned isn't stored anywhere, so there's nothing to write the change back to
+	let src = "
+		let make = fn() { obj { c: 0 inc: fn() { self.c += 1 self.c } } }
+		[make().inc() make().inc()]
+	";
+	assert_eq!(
+		eval(src),
+		IValue::Array(vec![IValue::i32(1), IValue::i32(1)])
+	);
+}
@@ -0,0 +1,132 @@
+//! the parser has to turn any input into statements and errors without panicking. \
+//! `basicparse/fuzz` does this with random inputs, these are the cases it's worth keeping around
+
+use std::{sync::mpsc, thread, time::Duration};
+
+use basicparse::{Error, Parser, Tokenizer};
+
+/// tokenizes and parses `src`, panicking only if either of them gets stuck
+fn parse(src: &str) -> Vec<Error> {
+	// every token or error has to move the tokenizer forward, or it would never finish
+	let tokens = Tokenizer::new(src).take(src.len() + 2).count();
+	assert!(tokens <= src.len() + 1, "tokenizer got stuck on {src:?}");
+
+	Parser::new(src).recover().1
+}
+
+/// like [parse], but panics if the parser takes longer than a couple of seconds instead of
+/// hanging the tests
+fn parse_in_time(src: String) -> Vec<Error> {
+	let (send, recv) = mpsc::channel();
+	thread::spawn(move || send.send(parse(&src)));
+	recv.recv_timeout(Duration::from_secs(5))
+		.expect("the parser got stuck (or panicked)")
+}
+
+fn examples() -> Vec<String> {
+	let root = concat!(env!("CARGO_MANIFEST_DIR"), "/..");
+	let mut examples = Vec::new();
+	for entry in std::fs::read_dir(root).expect("couldn't read the repo root") {
+		let path = entry.expect("couldn't read dir entry").path();
+		if path.extension().is_some_and(|ext| ext == "dl") {
+			examples.push(std::fs::read_to_string(path).expect("couldn't read example"));
+		}
+	}
+	assert!(!examples.is_empty());
+	examples
+}
+
+#[test]
+fn prefixes_and_suffixes_of_the_examples() {
+	for src in examples() {
+		for (i, _) in src.char_indices().step_by(3) {
+			parse(&src[..i]);
+			parse(&src[i..]);
+		}
+	}
+}
+
+#[test]
+fn examples_with_something_inserted() {
+	let inserts = [
+		"(",
+		")",
+		"[",
+		"]",
+		"{",
+		"}",
+		"\"",
+		"f\"{",
+		"\\",
+		"'",
+		"'a:",
+		"..",
+		".",
+		"-",
+		"!",
+		"=",
+		"=>",
+		"+=",
+		"obj",
+		"fn",
+		"let",
+		"match",
+		"for",
+		"in",
+		"if",
+		"else",
+		"loop",
+		"while",
+		"break",
+		"return",
+		"0.",
+		"99999999999999999999",
+		"é",
+		"|",
+		"#",
+		",",
+	];
+	for src in examples() {
+		for (i, _) in src.char_indices().step_by(61) {
+			for insert in inserts {
+				parse(&format!("{}{insert}{}", &src[..i], &src[i..]));
+			}
+		}
+	}
+}
+
+#[test]
+fn deep_nesting_is_an_error() {
+	let deep = [
+		format!("{}1{}", "(".repeat(1000), ")".repeat(1000)),
+		format!("{}{}", "[".repeat(1000), "]".repeat(1000)),
+		format!("{}{}", "if 1 {".repeat(1000), "}".repeat(1000)),
+		format!("{}{}", "f\"{".repeat(1000), "}\"".repeat(1000)),
+		format!("{}x", "-".repeat(1000)),
+		format!("{}x", "!".repeat(1000)),
+		format!("2{}", " ** 2".repeat(1000)),
+		"([{".repeat(1000),
+		// with the innermost statement on a line of its own, recovering from the error used to
+		// get stuck on it forever
+		format!("{}x\n{}", "loop {\n".repeat(64), "}".repeat(64)),
+		format!("{}x\n{}", "loop {\n".repeat(1000), "}".repeat(1000)),
+		format!("{}x\n{}", "fn() {\n".repeat(64), "}".repeat(64)),
+	];
+	for src in deep {
+		let errors = parse_in_time(src.clone());
+		assert!(
+			errors
+				.iter()
+				.any(|err| matches!(err.root(), Error::TooDeep(_))),
+			"{src:?}: {errors:#?}"
+		);
+	}
+}
+
+#[test]
+fn reasonable_nesting_is_fine() {
+	let src = format!("{}1{}", "(".repeat(20), ")".repeat(20));
+	assert_eq!(parse(&src).len(), 0);
+	let src = format!("{}{}", "if 1 { let x = [1 [2]] ".repeat(10), "}".repeat(10));
+	assert_eq!(parse(&src).len(), 0);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use basicparse::Error;
+use common::{eval, parse};
+use interpret::IValue;
+
+#[test]
+fn number_literals_have_the_right_values() {
+	for (src, expected) in [
+		("42", 42),
+		("1_000_000", 1_000_000),
+		("0xff", 255),
+		("0XFF", 255),
+		("0o17", 15),
+		("0b1010", 10),
+		("0b_1010_1010", 170),
+		("-0x10", -16),
+		("2147483647", i32::MAX),
+		("-2147483648", i32::MIN),
+		("- 2147483648", i32::MIN),
+	] {
+		assert_eq!(eval(src), IValue::i32(expected), "{src}");
+	}
+	for (src, expected) in [
+		("1.5", 1.5),
+		("1e3", 1000.0),
+		("2.5E-1", 0.25),
+		("1_0.2_5", 10.25),
+		("-1.5e+2", -150.0),
+	] {
+		assert_eq!(eval(src), IValue::f32(expected), "{src}");
+	}
+}
+
+#[test]
+fn negative_literals_work_in_patterns() {
+	let src = "match -2147483648 { -2147483648 => 1, _ => 2 }";
+	assert_eq!(eval(src), IValue::i32(1));
+}
+
+#[test]
+fn integer_literals_dont_overflow_into_floats() {
+	for lit in ["2147483648", "-2147483649", "0x1_0000_0000", "99999999999"] {
+		let err = parse(lit).unwrap_err();
+		assert!(
+			matches!(err.root(), Error::IntLitOverflow(got) if got == lit),
+			"{lit}: {err}"
+		);
+	}
+
+	let err = parse("let a = 1\nlet b = 1 + 2147483648").unwrap_err();
+	assert_eq!(err.span().map(|span| (span.line, span.col)), Some((2, 13)));
+}
+
+#[test]
+fn invalid_number_literals_are_errors() {
+	for lit in ["0x", "0xg", "0b102", "0o8", "1abc", "0x-1"] {
+		let err = parse(lit).unwrap_err();
+		assert!(
+			matches!(err.root(), Error::InvalidNumLit(_)),
+			"{lit}: {err}"
+		);
+	}
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::{eval, fail};
+use interpret::{Error, IValue};
+
+const NESTED: &str = "
+	let none = None
+	let a = obj { b: obj { list: [1 2 3] } }
+";
+
+#[test]
+fn optional_chaining_gives_none() {
+	let eval = |src| eval(&format!("{NESTED} {src}"));
+	assert_eq!(eval("a?.b?.list?.[1]"), IValue::i32(2));
+	assert_eq!(eval("a?.b?.list?.2"), IValue::i32(3));
+	assert_eq!(eval("none?.b?.list?.[1]"), IValue::None());
+	assert_eq!(eval("a?.missing?.list"), IValue::None());
+	assert_eq!(eval("a.b?.missing"), IValue::None());
+}
+
+#[test]
+fn optional_chaining_cuts_the_whole_chain_short() {
+	let eval = |src| eval(&format!("{NESTED} {src}"));
+	assert_eq!(eval("none?.b.list"), IValue::None());
+	assert_eq!(eval("none?.b.list.[0]"), IValue::None());
+	assert_eq!(eval("a?.missing.list.[0]"), IValue::None());
+	assert_eq!(eval("none?.method()"), IValue::None());
+	assert_eq!(eval("none?.b.method(1, 2)"), IValue::None());
+	assert_eq!(eval("a.b?.missing()"), IValue::None());
+
+	// the arguments don't get evaluated if the call is cut short
+	let src = "
+		let calls = 0
+		let arg = fn() { calls += 1 0 }
+		none?.f(arg())
+		calls
+	";
+	assert_eq!(eval(src), IValue::i32(0));
+
+	let src = "let o = obj { c: 1 get: fn() { self.c } } o?.get()";
+	assert_eq!(eval(src), IValue::i32(1));
+}
+
+#[test]
+fn plain_indexing_still_errors() {
+	assert!(matches!(
+		fail(&format!("{NESTED} none.b")),
+		Error::InvalidIndex { .. }
+	));
+	// the chain ends at the parens
+	assert!(matches!(
+		fail(&format!("{NESTED} let list = (a?.missing).list")),
+		Error::InvalidIndex { .. }
+	));
+	// only the `?.`s can cut it short
+	assert!(matches!(
+		fail(&format!("{NESTED} a?.b.missing")),
+		Error::InvalidIndex { .. }
+	));
+}
+
+#[test]
+fn coalescing() {
+	let eval = |src| eval(&format!("{NESTED} {src}"));
+	assert_eq!(eval("none ?? 5"), IValue::i32(5));
+	assert_eq!(eval("None ?? 3"), IValue::i32(3));
+	assert_eq!(eval("None"), IValue::None());
+	assert_eq!(eval("None == none"), IValue::bool(true));
+	assert_eq!(eval("0 ?? 5"), IValue::i32(0));
+	assert_eq!(eval("none ?? none ?? 7"), IValue::i32(7));
+	assert_eq!(eval("a?.missing ?? 1 + 1"), IValue::i32(2));
+	assert_eq!(
+		eval("a?.b?.list?.[5] ?? \"nope\""),
+		IValue::String("nope".into())
+	);
+}
+
+#[test]
+fn coalescing_only_evaluates_the_fallback_if_needed() {
+	let src = "
+		let calls = 0
+		let fallback = fn() { calls += 1 0 }
+		1 ?? fallback()
+		calls
+	";
+	assert_eq!(eval(src), IValue::i32(0));
+}
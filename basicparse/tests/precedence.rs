@@ -0,0 +1,75 @@
+mod common;
+
+use common::eval;
+use interpret::IValue;
+
+#[test]
+fn subtraction_is_left_associative() {
+	assert_eq!(eval("10 - 3 - 2"), IValue::i32(5));
+	assert_eq!(eval("1 - 2 + 3"), IValue::i32(2));
+	assert_eq!(eval("10 - (3 - 2)"), IValue::i32(9));
+}
+
+#[test]
+fn comparison_binds_looser_than_addition() {
+	assert_eq!(eval("1 + 1 == 2"), IValue::bool(true));
+	assert_eq!(eval("2 == 1 + 1"), IValue::bool(true));
+	assert_eq!(eval("1 + 2 > 2 + 0"), IValue::bool(true));
+	assert_eq!(eval("5 - 1 <= 3"), IValue::bool(false));
+}
+
+#[test]
+fn logical_operators_bind_looser_than_comparison() {
+	assert_eq!(eval("1 == 1 && 2 == 3"), IValue::bool(false));
+	assert_eq!(eval("1 == 2 || 2 == 2"), IValue::bool(true));
+	// && binds tighter than ||
+	assert_eq!(eval("1 == 1 || 1 == 2 && 1 == 2"), IValue::bool(true));
+}
+
+#[test]
+fn indexing_and_calls_bind_tightest() {
+	assert_eq!(eval("let a = [1 2] a.1 - a.0 - 1"), IValue::i32(0));
+	assert_eq!(
+		eval("let f = fn(x) { x - 1 } f(3) - f(2) - 1"),
+		IValue::i32(0)
+	);
+}
+
+#[test]
+fn multiplication_binds_tighter_than_addition() {
+	assert_eq!(eval("1 + 2 * 3"), IValue::i32(7));
+	assert_eq!(eval("2 * 3 + 1"), IValue::i32(7));
+	assert_eq!(eval("7 - 6 / 3"), IValue::i32(5));
+	assert_eq!(eval("1 + 7 % 4"), IValue::i32(4));
+	assert_eq!(eval("2 * 3 == 6"), IValue::bool(true));
+}
+
+#[test]
+fn multiplicative_operators_are_left_associative() {
+	assert_eq!(eval("12 / 3 / 2"), IValue::i32(2));
+	assert_eq!(eval("12 / 3 * 2"), IValue::i32(8));
+	assert_eq!(eval("17 % 5 % 2"), IValue::i32(0));
+}
+
+#[test]
+fn power_is_right_associative_and_binds_tightest() {
+	assert_eq!(eval("2 ** 3 ** 2"), IValue::i32(512));
+	assert_eq!(eval("(2 ** 3) ** 2"), IValue::i32(64));
+	assert_eq!(eval("2 * 3 ** 2"), IValue::i32(18));
+}
+
+#[test]
+fn prefix_operators_bind_tighter_than_binary_operators() {
+	assert_eq!(eval("let x = 3 let y = -x + 1 y"), IValue::i32(-2));
+	assert_eq!(eval("let x = 3 let y = 2 - -x y"), IValue::i32(5));
+	assert_eq!(eval("let x = 3 let y = -x ** 2 y"), IValue::i32(9));
+	assert_eq!(eval("!1 == 2"), IValue::bool(false));
+	assert_eq!(eval("!(1 == 2)"), IValue::bool(true));
+	assert_eq!(eval("!!1"), IValue::bool(true));
+}
+
+#[test]
+fn prefix_operators_bind_looser_than_indexing_and_calls() {
+	assert_eq!(eval("let a = [1 2] let b = -a.1 b"), IValue::i32(-2));
+	assert_eq!(eval("let f = fn(x) { x == 1 } !f(1)"), IValue::bool(false));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use basicparse::{Token, Tokenizer};
+use common::{eval, fail};
+use interpret::{Error, IValue, Range};
+
+fn ints(ints: impl IntoIterator<Item = i32>) -> IValue {
+	IValue::Array(ints.into_iter().map(IValue::i32).collect())
+}
+
+#[test]
+fn range_tokens() {
+	let tokens = Tokenizer::new("0..10 0..=10 [a ..b]")
+		.map(|t| t.unwrap().token)
+		.collect::<Vec<_>>();
+	assert_eq!(
+		tokens[..3],
+		[
+			Token::NumLit("0".into()),
+			Token::DotDot,
+			Token::NumLit("10".into())
+		]
+	);
+	assert_eq!(tokens[4], Token::DotDotEq);
+}
+
+#[test]
+fn ranges_are_native_values() {
+	assert_eq!(eval("0..10"), IValue::Range(Range::new(0, 10)));
+	assert_eq!(eval("0..=10"), IValue::Range(Range::inclusive(0, 10)));
+	// looser than arithmetic, tighter than comparisons
+	assert_eq!(eval("let n = 4 1..n - 1"), IValue::Range(Range::new(1, 3)));
+	assert!(matches!(fail("0..\"a\""), Error::InvalidRange { .. }));
+}
+
+#[test]
+fn for_loops_iterate_ranges() {
+	let src = "
+		let out = []
+		for i in -2..2 { out += [i] }
+		for i in 5..=6 { out += [i] }
+		for i in 3..1 { out += [i] }
+		out
+	";
+	assert_eq!(eval(src), ints([-2, -1, 0, 1, 5, 6]));
+}
+
+#[test]
+fn len_indexing_and_slicing() {
+	assert_eq!(eval("(2..7).len"), IValue::i32(5));
+	assert_eq!(eval("(5..2).len"), IValue::i32(0));
+	assert_eq!(eval("(2..7).1"), IValue::i32(3));
+	assert_eq!(eval("(2..7).5"), IValue::None());
+	assert_eq!(
+		eval("let r = 10..=20 r.[3..5]"),
+		IValue::Range(Range::new(13, 15))
+	);
+	assert_eq!(
+		eval("let r = 10..=20 r.[8..100]"),
+		IValue::Range(Range::new(18, 21))
+	);
+	assert_eq!(eval("[1 2 3 4].[1..3]"), ints([2, 3]));
+	assert_eq!(eval("[1 2 3 4].[3..1]"), ints([]));
+}
+
+#[test]
+fn slices_get_clamped_to_what_they_slice() {
+	// negative starts count as 0, ends past the end count as the end
+	assert_eq!(eval("[1 2 3 4].[-5..=0]"), ints([1]));
+	assert_eq!(eval("[1 2 3 4].[-2..2]"), ints([1, 2]));
+	assert_eq!(eval("[1 2 3 4].[2..100]"), ints([3, 4]));
+	assert_eq!(eval("[1 2 3 4].[-2..100]"), ints([1, 2, 3, 4]));
+	assert_eq!(eval("[1 2 3 4].[-5..-1]"), ints([]));
+	assert_eq!(eval("[1 2 3 4].[10..20]"), ints([]));
+	assert_eq!(
+		eval("let r = 10..20 r.[-3..2]"),
+		IValue::Range(Range::new(10, 12))
+	);
+}
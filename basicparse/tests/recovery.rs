@@ -0,0 +1,94 @@
+use basicparse::{Error, Parser};
+use interpret::{Context, IValue};
+use langlib::{Expr, Reach, Statement, Value};
+
+fn unspanned(statements: &[Statement]) -> Vec<&Statement> {
+	statements.iter().map(Statement::unspanned).collect()
+}
+
+#[test]
+fn every_error_gets_reported() {
+	let src = "
+		let x = 5
+		let = 3
+		let f = fn(a, a) { a }
+		let y = 6
+		break
+		x + y
+	";
+	let (statements, errors) = Parser::new(src).recover();
+	assert_eq!(errors.len(), 3, "{errors:#?}");
+	let lines: Vec<_> = errors.iter().map(|err| err.span().unwrap().line).collect();
+	assert_eq!(lines, [3, 4, 6]);
+
+	let statements = unspanned(&statements);
+	assert_eq!(statements.len(), 6);
+	assert!(matches!(statements[0], Statement::SetVariable(..)));
+	assert_eq!(statements[1], &Statement::Error);
+	assert_eq!(statements[2], &Statement::Error);
+	assert!(matches!(statements[3], Statement::SetVariable(..)));
+	assert_eq!(statements[4], &Statement::Error);
+	assert!(matches!(statements[5], Statement::Expr(_)));
+}
+
+#[test]
+fn errors_in_blocks_stay_in_the_block() {
+	let src = "
+		let f = fn() {
+			let [..a ..b] = [1]
+			3
+		}
+		let y = 1
+	";
+	let (statements, errors) = Parser::new(src).recover();
+	assert_eq!(errors.len(), 1, "{errors:#?}");
+	assert_eq!(errors[0].span().unwrap().line, 3);
+
+	let statements = unspanned(&statements);
+	assert_eq!(statements.len(), 2);
+	let Statement::SetVariable(_, f) = statements[0] else {
+		panic!(
+			"expected the function to still get declared, got {:?}",
+			statements[0]
+		);
+	};
+	let Expr::Reach(Reach::Value(Value::Function(f))) = f.unspanned() else {
+		panic!("expected a function, got {f:?}");
+	};
+	let body = unspanned(&f.block.0);
+	assert_eq!(body[0], &Statement::Error);
+	assert!(matches!(body[1], Statement::Expr(_)));
+}
+
+#[test]
+fn tokenizer_errors_get_reported_too() {
+	let (statements, errors) = Parser::new("let a = 1\nlet b = \"\\q\"\na").recover();
+	assert!(matches!(errors.as_slice(), [err] if err.to_string().contains("\\q")));
+	assert_eq!(statements.len(), 3);
+}
+
+#[test]
+fn valid_code_parses_the_same() {
+	let src = "let a = fn(x) { x * 2 } a(3)";
+	let (recovered, errors) = Parser::new(src).recover();
+	assert!(errors.is_empty());
+	let statements = basicparse::preproc(Parser::new(src).statements())
+		.collect::<Result<Vec<_>, Error>>()
+		.unwrap();
+	assert_eq!(recovered, statements);
+}
+
+#[test]
+fn running_an_error_node_fails() {
+	let (statements, _) = Parser::new("let a = 1\nlet = 2\na").recover();
+	let err = Context::default()
+		.exec(statements)
+		.expect_err("expected an error");
+	assert!(matches!(
+		err,
+		interpret::Error::At { err, .. } if matches!(*err, interpret::Error::Unparsed)
+	));
+
+	let (statements, _) = Parser::new("let a = 1\na").recover();
+	assert_eq!(Context::default().exec(statements).unwrap(), IValue::i32(1));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use basicparse::{Error, Parser};
+use common::{parse, unlocated};
+use interpret::Context;
+use langlib::Span;
+
+fn span(start: usize, end: usize, line: usize, col: usize) -> Span {
+	Span {
+		start,
+		end,
+		line,
+		col,
+	}
+}
+
+#[test]
+fn statements_know_where_they_are() {
+	let parsed = parse("let a = 1\n\n  a + 2").unwrap();
+	let spans: Vec<_> = parsed.iter().map(|stmt| stmt.span()).collect();
+	assert_eq!(spans, [Some(span(0, 9, 1, 1)), Some(span(13, 18, 3, 3))]);
+}
+
+#[test]
+fn parse_errors_know_where_they_are() {
+	let err = parse("let a = 1\nlet b 2").unwrap_err();
+	assert!(matches!(err.root(), Error::ExpectedEqLet));
+	assert_eq!(err.span().map(|span| (span.line, span.col)), Some((2, 1)));
+
+	// errors inside of groups point into the group
+	let err = parse("let a = [\n  1\n  fn 2]").unwrap_err();
+	assert!(matches!(err.root(), Error::ExpectedFnDeclParens));
+	assert_eq!(err.span().map(|span| (span.line, span.col)), Some((3, 3)));
+
+	let (_, errs) = Parser::new("let = 1\nlet b = 2\nlet = 3").recover();
+	let lines: Vec<_> = errs.iter().map(|err| err.span().unwrap().line).collect();
+	assert_eq!(lines, [1, 3]);
+}
+
+#[test]
+fn runtime_errors_know_where_they_are() {
+	let parsed = parse("let a = 1\nlet b = a +\n  c").unwrap();
+	let err = Context::default().exec(parsed).unwrap_err();
+	assert_eq!(err.span(), Some(span(24, 25, 3, 3)));
+	assert!(
+		matches!(unlocated(err), interpret::Error::VariableDoesntExist(name, _) if name == "c")
+	);
+}
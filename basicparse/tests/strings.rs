@@ -0,0 +1,49 @@
+mod common;
+
+use basicparse::Error;
+use common::{eval, parse};
+use interpret::IValue;
+
+#[test]
+fn string_literals_evaluate_to_their_text() {
+	assert_eq!(eval(r#""a\t\"b\"""#), IValue::String("a\t\"b\"".into()));
+	assert_eq!(
+		eval(r##"r#"C:\dir "x""#"##),
+		IValue::String(r#"C:\dir "x""#.into())
+	);
+}
+
+#[test]
+fn strings_are_quoted_inside_of_arrays() {
+	// printing a string prints its text, but inside of an array it's quoted like a literal again
+	assert_eq!(eval(r#""a\"b\n""#).to_string(), "a\"b\n");
+	assert_eq!(eval(r#"["a\"b\n" 1]"#).to_string(), r#"[ "a\"b\n" 1 ]"#);
+}
+
+#[test]
+fn invalid_escapes_point_at_the_escape() {
+	let err = parse("let a = 1\nlet b = \"ok \\x\"").unwrap_err();
+	assert!(matches!(err.root(), Error::InvalidEscape(escape) if escape == "\\x"));
+	assert_eq!(err.span().map(|span| (span.line, span.col)), Some((2, 13)));
+}
+
+#[test]
+fn format_strings_display_their_values() {
+	let src = r#"
+		let i = 3
+		let a = [1 "b"]
+		f"{i}: {a} {i + 2}{"!"}""#;
+	assert_eq!(eval(src), IValue::String(r#"3: [ 1 "b" ] 5!"#.into()));
+	assert_eq!(eval(r#"f"\{{1}\}""#), IValue::String("{1}".into()));
+}
+
+#[test]
+fn errors_in_format_strings_point_into_them() {
+	let err = parse("let a = 1\nf\"a {fn 2}\"").unwrap_err();
+	assert!(matches!(err.root(), Error::ExpectedFnDeclParens));
+	assert_eq!(err.span().map(|span| (span.line, span.col)), Some((2, 6)));
+
+	// running out of tokens points at the start of what was being read, like with parens
+	let err = parse("let a = 1\nf\"a {a +}\"").unwrap_err();
+	assert_eq!(err.span().map(|span| (span.line, span.col)), Some((2, 1)));
+}
@@ -1,9 +1,13 @@
-use std::{env, fs, path::PathBuf};
+use std::{
+	env, fs,
+	path::{Path, PathBuf},
+	rc::Rc,
+};
 
 use anyhow::{Context as _, anyhow};
-use basicparse::{Parser, preproc};
-use interpret::{Context, IValue};
-use langlib::{Block, Statement};
+use basicparse::Parser;
+use interpret::{Context, IValue, Loader};
+use langlib::Statement;
 use rustyline::DefaultEditor;
 
 mod std_builtins;
@@ -17,7 +21,7 @@ fn main() -> anyhow::Result<()> {
 
 	if path.exists() {
 		let file = fs::read_to_string(&path).unwrap();
-		let out = eval(&file);
+		let out = eval(&file, &path);
 		println!("{out:?}");
 		Ok(())
 	} else {
@@ -29,11 +33,12 @@ fn main() -> anyhow::Result<()> {
 		}
 
 		eprintln!(
-			"welcome to dynlang repl\nuse .import <file path> to import .dl files into the context\n"
+			"welcome to dynlang repl\nuse .import <file path> to import .dl files into the context\nor `import \"<file path>\" as name` to import one as a module\n"
 		);
 
 		let mut ctx = Context::default();
 		ctx.builtins(std_builtins::builtins());
+		ctx.imports(loader(), None);
 		let a = loop {
 			let line = match rl.readline(" > ") {
 				Ok(a) => a,
@@ -56,11 +61,11 @@ fn main() -> anyhow::Result<()> {
 			};
 
 			match parse(&line) {
-				Ok(parsed) => match ctx.resolve_block(&Block(parsed)) {
+				Ok(parsed) => match ctx.exec(parsed) {
 					Ok(a) => println!("{a}"),
 					Err(err) => eprintln!("failed to execute: {err}"),
 				},
-				Err(err) => eprintln!("failed to parse: {err}"),
+				Err(errors) => eprintln!("failed to parse:\n{}", report(&errors)),
 			}
 		};
 		rl.save_history(history_path)?;
@@ -68,19 +73,39 @@ fn main() -> anyhow::Result<()> {
 	}
 }
 
-fn parse(src: &str) -> basicparse::Result<Vec<Statement>> {
-	let parser = Parser::new(src);
-	let parser = preproc(parser.statements());
-
-	let parsed = parser.collect::<Result<Vec<_>, _>>()?;
-
-	Ok(parsed)
+/// parses everything, returning every error if there's at least one
+fn parse(src: &str) -> Result<Vec<Statement>, Vec<basicparse::Error>> {
+	let (parsed, errors) = Parser::new(src).recover();
+	if errors.is_empty() {
+		Ok(parsed)
+	} else {
+		Err(errors)
+	}
+}
+/// lists the errors one after the other, with an empty line between them
+fn report(errors: &[basicparse::Error]) -> String {
+	let errors = errors.iter().map(|err| err.to_string());
+	errors.collect::<Vec<_>>().join("\n\n")
+}
+/// the module loader for `import` statements, every module gets the std builtins
+fn loader() -> Rc<Loader> {
+	Loader::new(
+		|src| parse(src).map_err(|errors| report(&errors)),
+		std_builtins::builtins(),
+	)
 }
-fn eval(src: &str) -> anyhow::Result<IValue> {
-	let parsed = parse(src)?;
+fn eval(src: &str, file: &Path) -> anyhow::Result<IValue> {
+	let parsed = parse(src).map_err(|errors| {
+		anyhow!(
+			"{} error(s) while parsing:\n{}",
+			errors.len(),
+			report(&errors)
+		)
+	})?;
 
 	let mut ctx = interpret::Context::new::<IValue, _>([]);
 	ctx.builtins(std_builtins::builtins());
+	ctx.imports(loader(), Some(file));
 	ctx.exec(parsed)
 		.map_err(|err| anyhow!("{err}"))
 		.with_context(|| "execution failed")
@@ -1,7 +1,17 @@
-use interpret::{BuiltinBuilder, BuiltinFn, Error, IValue, Result};
+use interpret::{BuiltinBuilder, BuiltinFn, Error, IValue, Result, utils::args};
 use langlib::Value;
 
-fn print_noln(value: IValue) -> Result<IValue> {
+/// prints every arg, separated by spaces
+fn print_noln(values: Vec<IValue>) -> Result<IValue> {
+	for (i, value) in values.into_iter().enumerate() {
+		if i > 0 {
+			print!(" ");
+		}
+		print_value(value);
+	}
+	Ok(IValue::None())
+}
+fn print_value(value: IValue) {
 	match value {
 		IValue::Value(Value::bool(a)) => print!("{a}"),
 		IValue::Value(Value::i32(a)) => print!("{a}"),
@@ -16,10 +26,9 @@ fn print_noln(value: IValue) -> Result<IValue> {
 
 		_ => print!("{value:?}"),
 	}
-	Ok(IValue::None())
 }
-fn print(value: IValue) -> Result<IValue> {
-	let ret = print_noln(value);
+fn print(values: Vec<IValue>) -> Result<IValue> {
+	let ret = print_noln(values);
 	println!();
 	ret
 }
@@ -32,7 +41,8 @@ fn print_noln_builtin(builder: &mut BuiltinBuilder) -> BuiltinFn {
 }
 
 fn len_builtin(builder: &mut BuiltinBuilder) -> BuiltinFn {
-	fn len(val: IValue) -> Result<IValue> {
+	fn len(vals: Vec<IValue>) -> Result<IValue> {
+		let [val] = args("len", vals)?;
 		let len = interpret::utils::len(&val);
 		Ok(if let Some(len) = len {
 			IValue::i32(len as _)
@@ -44,14 +54,16 @@ fn len_builtin(builder: &mut BuiltinBuilder) -> BuiltinFn {
 }
 
 fn to_string_builtin(builder: &mut BuiltinBuilder) -> BuiltinFn {
-	fn to_string(val: IValue) -> Result<IValue> {
+	fn to_string(vals: Vec<IValue>) -> Result<IValue> {
+		let [val] = args("to_string", vals)?;
 		Ok(IValue::String(format!("{val}")))
 	}
 	builder.new_fn("to_string", to_string)
 }
 
 fn obj_keys(builder: &mut BuiltinBuilder) -> BuiltinFn {
-	fn obj_keys(val: IValue) -> Result<IValue> {
+	fn obj_keys(vals: Vec<IValue>) -> Result<IValue> {
+		let [val] = args("obj_keys", vals)?;
 		macro_rules! implementation {
 			($obj:expr) => {{ $obj.keys().cloned().map(IValue::String).collect() }};
 		}
@@ -70,18 +82,8 @@ fn obj_keys(builder: &mut BuiltinBuilder) -> BuiltinFn {
 }
 
 fn obj_merge(builder: &mut BuiltinBuilder) -> BuiltinFn {
-	fn obj_merge(val: IValue) -> Result<IValue> {
-		let (a, b) = match val {
-			IValue::Array(arr) if arr.len() == 2 => {
-				let mut arr = arr.into_iter();
-				(arr.next().unwrap(), arr.next().unwrap())
-			}
-			_ => {
-				return Err(Error::Runtime(format!(
-					"invalid argument to obj_merge: expected an array with two object elements"
-				)));
-			}
-		};
+	fn obj_merge(vals: Vec<IValue>) -> Result<IValue> {
+		let [a, b] = args("obj_merge", vals)?;
 		match (a, b) {
 			(IValue::Object(a), IValue::Object(b)) => {
 				Ok(IValue::Object(a.into_iter().chain(b).collect()))
@@ -95,7 +97,8 @@ fn obj_merge(builder: &mut BuiltinBuilder) -> BuiltinFn {
 }
 
 fn throw_error(builder: &mut BuiltinBuilder) -> BuiltinFn {
-	fn throw_error(val: IValue) -> Result<IValue> {
+	fn throw_error(vals: Vec<IValue>) -> Result<IValue> {
+		let [val] = args("throw_error", vals)?;
 		Err(Error::Runtime(format!("program threw error:\n{val}")))
 	}
 	builder.new_fn("throw_error", throw_error)
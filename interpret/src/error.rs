@@ -4,6 +4,8 @@ use crate::*;
 pub enum Error {
 	#[error("runtime error:\n{0}")]
 	Runtime(String),
+	#[error("at {span}: {err}")]
+	At { span: Span, err: Box<Self> },
 
 	#[error("attempted to {1} variable '{0}' that doesn't exist")]
 	VariableDoesntExist(String, VariableAccessType),
@@ -12,23 +14,87 @@ pub enum Error {
 	InvalidAddition { a: IValue, b: IValue },
 	#[error("attempted invalid subtraction operation {a:?} - {b:?}")]
 	InvalidSubtraction { a: IValue, b: IValue },
+	#[error("attempted invalid negation -{0:?}")]
+	InvalidNegation(IValue),
+	#[error("attempted invalid multiplication operation {a:?} * {b:?}")]
+	InvalidMultiplication { a: IValue, b: IValue },
+	#[error("attempted invalid division operation {a:?} / {b:?}")]
+	InvalidDivision { a: IValue, b: IValue },
+	#[error("attempted invalid remainder operation {a:?} % {b:?}")]
+	InvalidRemainder { a: IValue, b: IValue },
+	#[error("attempted invalid power operation {a:?} ** {b:?}")]
+	InvalidPower { a: IValue, b: IValue },
+	#[error("ranges go between two whole numbers, got {a:?}..{b:?}")]
+	InvalidRange { a: IValue, b: IValue },
+	#[error("attempted to divide by zero: {a:?} {op} {b:?}")]
+	DivisionByZero {
+		op: &'static str,
+		a: IValue,
+		b: IValue,
+	},
+	#[error("integer overflow: the result of {a:?} {op} {b:?} doesn't fit in an i32")]
+	Overflow {
+		op: &'static str,
+		a: IValue,
+		b: IValue,
+	},
+	#[error("integer overflow: the result of -{0:?} doesn't fit in an i32")]
+	NegOverflow(IValue),
 	#[error("attempted invalid greater than operation {a:?} > {b:?}")]
 	InvalidGt { a: IValue, b: IValue },
 	#[error("attempted invalid less than operation {a:?} < {b:?}")]
 	InvalidLt { a: IValue, b: IValue },
+	#[error("attempted invalid greater than or equal operation {a:?} >= {b:?}")]
+	InvalidGe { a: IValue, b: IValue },
+	#[error("attempted invalid less than or equal operation {a:?} <= {b:?}")]
+	InvalidLe { a: IValue, b: IValue },
 	#[error("invalid indexing of value: {a:?}.{i:?}")]
 	InvalidIndex { a: IValue, i: Index },
+	#[error(
+		"can't assign to {a:?}.{i:?}, only existing elements of arrays and fields of objects can be assigned to"
+	)]
+	InvalidIndexAssign { a: IValue, i: Index },
 	#[error("attempted to call a variable that isn't a function: {0:?}")]
 	NotAFunction(IValue),
+	#[error("value {val:?} doesn't fit the pattern `{pattern}`")]
+	PatternMismatch { pattern: Pattern, val: IValue },
+	#[error("none of the arms of the match expression matched {0:?}")]
+	NoMatchingArm(IValue),
 
+	#[error("`{0}` is a const, it can't be changed")]
+	ModifiedConst(String),
 	#[error("missing argument to function: expected arg {0}")]
 	MissingArg(String),
+	#[error("{f} doesn't have a parameter called `{name}`")]
+	UnknownArg { f: String, name: String },
+	#[error("argument `{0}` is passed both by position and by name")]
+	ArgPassedTwice(String),
+	#[error("{f} takes {expected} arguments, but it got {got}")]
+	WrongArgCount {
+		f: String,
+		expected: usize,
+		got: usize,
+	},
+	#[error("can't import anything here, there's no module loader (see Context::imports)")]
+	ImportsUnavailable,
+	#[error("couldn't read module \"{path}\": {err}")]
+	ImportRead { path: String, err: String },
+	#[error("failed to parse module \"{path}\":\n{err}")]
+	ImportParse { path: String, err: String },
+	#[error("in module \"{path}\":\n{err}")]
+	InModule { path: String, err: Box<Self> },
+	#[error("import cycle: {}", .0.join(" imports "))]
+	ImportCycle(Vec<String>),
+	#[error("module \"{path}\" doesn't export `{name}`")]
+	NotExported { path: String, name: String },
+	#[error("attempted to run a statement that failed to parse")]
+	Unparsed,
 	#[error("invalid value generated from expression in .[] index brackets: {0:?}")]
 	InvalidExprFromIntoIndex(IValue),
 
-	#[error("break got called and nothing caught it apparently")]
-	/// this might not be the best solution cause it can travel through different contexts but i literally don't care
-	Break,
+	#[error("`{0}` got used outside of a loop or function that could catch it")]
+	/// basicparse rejects stray break, continue and return, so this only happens with hand-built ASTs
+	Uncaught(Signal),
 	#[error(
 		"value passed into for loop is not an iterator: {0:?}\niterators are objects with methods (.next(), .len())"
 	)]
@@ -45,6 +111,53 @@ pub enum Error {
 	)]
 	Impossible1,
 }
+impl Error {
+	/// attaches the given location to the error, unless it already knows a more precise one
+	pub fn at(self, span: Span) -> Self {
+		match self {
+			Self::At { .. } => self,
+			err => Self::At {
+				span,
+				err: Box::new(err),
+			},
+		}
+	}
+	/// the error for when `a op b` failed with `err` \
+	/// `invalid` creates the error for when the operation isn't legal for these types
+	pub fn arith(
+		err: ArithError,
+		op: &'static str,
+		a: IValue,
+		b: IValue,
+		invalid: fn(IValue, IValue) -> Self,
+	) -> Self {
+		match err {
+			ArithError::Invalid => invalid(a, b),
+			ArithError::DivisionByZero => Self::DivisionByZero { op, a, b },
+			ArithError::Overflow => Self::Overflow { op, a, b },
+		}
+	}
+	/// the error for when `a op b` failed with `err`, like [Error::arith] with the
+	/// invalid operation error that belongs to `op`
+	pub fn arith_op(err: ArithError, op: ArithOp, a: IValue, b: IValue) -> Self {
+		let invalid: fn(IValue, IValue) -> Self = match op {
+			ArithOp::Add => |a, b| Self::InvalidAddition { a, b },
+			ArithOp::Sub => |a, b| Self::InvalidSubtraction { a, b },
+			ArithOp::Mul => |a, b| Self::InvalidMultiplication { a, b },
+			ArithOp::Div => |a, b| Self::InvalidDivision { a, b },
+			ArithOp::Rem => |a, b| Self::InvalidRemainder { a, b },
+			ArithOp::Pow => |a, b| Self::InvalidPower { a, b },
+		};
+		Self::arith(err, op.symbol(), a, b, invalid)
+	}
+	/// the location in the source code where this error happened, if known
+	pub fn span(&self) -> Option<Span> {
+		match self {
+			Self::At { span, .. } => Some(*span),
+			_ => None,
+		}
+	}
+}
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
 #[derive(Clone, Debug, thiserror::Error)]
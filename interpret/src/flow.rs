@@ -0,0 +1,67 @@
+use std::fmt::Display;
+
+use crate::*;
+
+/// a `break`, `continue` or `return` on its way to the loop or function that handles it, or a
+/// `?.` that hit None on its way to the [Expr::OptionalChain] it's in
+#[derive(Clone, Debug, PartialEq)]
+pub enum Signal {
+	Break(Option<String>),
+	Continue(Option<String>),
+	Return(IValue),
+	ShortCircuit,
+}
+impl Signal {
+	/// true if it's a `break` or `continue` meant for the loop with the given label \
+	/// unlabeled ones are meant for the innermost loop
+	pub fn is_for_loop(&self, label: &Option<String>) -> bool {
+		match self {
+			Self::Break(target) | Self::Continue(target) => target.is_none() || target == label,
+			Self::Return(_) | Self::ShortCircuit => false,
+		}
+	}
+}
+impl Display for Signal {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let (keyword, label) = match self {
+			Self::Break(label) => ("break", label),
+			Self::Continue(label) => ("continue", label),
+			Self::Return(_) => ("return", &None),
+			Self::ShortCircuit => ("?.", &None),
+		};
+		write!(f, "{keyword}")?;
+		if let Some(label) = label {
+			write!(f, " '{label}")?;
+		}
+		Ok(())
+	}
+}
+
+/// why a block stopped before reaching its end: either something went wrong, or
+/// it's just a [Signal] that a loop or a function call is going to catch
+#[derive(Clone, Debug)]
+pub enum Unwind {
+	Error(Error),
+	Signal(Signal),
+}
+impl Unwind {
+	/// see [Error::at], signals are left as-is
+	pub fn at(self, span: Span) -> Self {
+		match self {
+			Self::Error(err) => Self::Error(err.at(span)),
+			signal => signal,
+		}
+	}
+	/// for when there's nothing left that could catch a signal
+	pub fn into_error(self) -> Error {
+		match self {
+			Self::Error(err) => err,
+			Self::Signal(signal) => Error::Uncaught(signal),
+		}
+	}
+}
+impl From<Error> for Unwind {
+	fn from(err: Error) -> Self {
+		Self::Error(err)
+	}
+}
@@ -1,6 +1,6 @@
 use std::{
 	cell::RefCell,
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	fmt::{Debug, Display},
 	rc::Rc,
 };
@@ -11,6 +11,15 @@ pub use error::*;
 mod val;
 pub use val::*;
 
+mod flow;
+pub use flow::*;
+
+mod range;
+pub use range::*;
+
+mod module;
+pub use module::*;
+
 pub mod utils;
 
 use langlib::*;
@@ -18,10 +27,39 @@ use langlib::*;
 #[derive(Clone, Default, PartialEq)]
 pub struct ContextData {
 	variables: HashMap<String, IValue>,
+	/// the names of the variables in this window that can't be changed
+	consts: HashSet<String>,
+	/// whether the `self` in this window got changed since it was set, so the method call it's
+	/// in knows to put it back where it came from
+	self_changed: bool,
 }
 #[derive(Clone, Default, PartialEq)]
 pub struct Context {
 	ctx: Vec<Rc<RefCell<ContextData>>>,
+	/// where the code is from, without one `import` doesn't work
+	module: Option<Module>,
+}
+
+/// a variable and the indexes leading to a value stored in it (`name.a.[i]`)
+pub type Place = (String, Vec<Index>);
+
+/// the value a method got called on, what `self` is inside of it
+pub struct Receiver {
+	pub val: IValue,
+	/// the variable and the indexes leading to where `val` is stored, if it's stored anywhere
+	pub place: Option<Place>,
+	/// set once the method changes `self`, `val` is the changed version then
+	pub changed: bool,
+}
+impl Receiver {
+	/// a receiver that isn't stored anywhere, changes to it stay in the receiver
+	pub fn new(val: IValue) -> Self {
+		Self {
+			val,
+			place: None,
+			changed: false,
+		}
+	}
 }
 
 impl Context {
@@ -29,9 +67,12 @@ impl Context {
 		let variables = variables.into_iter();
 		let data = ContextData {
 			variables: variables.map(|(k, v)| (k, v.into())).collect(),
+			consts: HashSet::new(),
+			self_changed: false,
 		};
 		Self {
 			ctx: vec![Rc::new(RefCell::new(data))],
+			module: None,
 		}
 	}
 	pub fn builtins<I: IntoIterator<Item = BuiltinFn>>(&mut self, builtins: I) {
@@ -42,7 +83,7 @@ impl Context {
 			.into_iter()
 			.map(|b| (b.name().to_string(), IValue::BuiltinFn(b)))
 			.collect();
-		self.set_variable("builtins".into(), IValue::Object(map));
+		self.set_const("builtins".into(), IValue::Object(map));
 	}
 
 	/// clones itself and appends a new context window to the list (making newly created variables automatically get placed in the new context window)
@@ -54,6 +95,7 @@ impl Context {
 				.cloned()
 				.chain(std::iter::once(Default::default()))
 				.collect(),
+			module: self.module.clone(),
 		}
 	}
 
@@ -87,6 +129,10 @@ impl Context {
 		for ctx in self.ctx.iter().rev() {
 			let mut ctx = ctx.borrow_mut();
 			if let Some(_) = ctx.variables.get(name) {
+				if ctx.consts.contains(name) {
+					return Err(Error::ModifiedConst(name.into()));
+				}
+				ctx.self_changed |= name == "self";
 				return ctx
 					.variables
 					.insert(name.into(), val)
@@ -98,15 +144,52 @@ impl Context {
 			VariableAccessType::Modify,
 		))
 	}
+	/// the part of the variable that `path` leads to (`name.a.[i]`)
+	pub fn get_path(&self, name: &str, path: &[Index]) -> Result<IValue> {
+		let mut val = self.get_variable(name)?;
+		for i in path {
+			val = match val.index(i) {
+				Some(inner) => inner,
+				None => {
+					return Err(Error::InvalidIndex {
+						a: val,
+						i: i.clone(),
+					});
+				}
+			};
+		}
+		Ok(val)
+	}
+	/// changes the part of the variable that `path` leads to (`name.a.[i] = val`) in place
+	pub fn modify_path(&self, name: &str, path: &[Index], val: impl Into<IValue>) -> Result<()> {
+		for ctx in self.ctx.iter().rev() {
+			let mut ctx = ctx.borrow_mut();
+			if ctx.consts.contains(name) {
+				return Err(Error::ModifiedConst(name.into()));
+			}
+			if let Some(var) = ctx.variables.get_mut(name) {
+				var.set_path(path, val.into())?;
+				ctx.self_changed |= name == "self";
+				return Ok(());
+			}
+		}
+		Err(Error::VariableDoesntExist(
+			name.into(),
+			VariableAccessType::Modify,
+		))
+	}
 	/// appends the new variable to the topmost context window, creating one if none exist
 	pub fn set_variable(&mut self, name: String, val: impl Into<IValue>) {
-		let val = val.into();
+		self.declare(name, val.into(), false);
+	}
+	/// like [Context::set_variable], but the variable can't be changed afterwards (it can still be
+	/// shadowed by declaring a new one with the same name)
+	pub fn set_const(&mut self, name: String, val: impl Into<IValue>) {
+		self.declare(name, val.into(), true);
+	}
+	fn declare(&mut self, name: String, val: IValue, constant: bool) {
 		if self.ctx.len() == 0 {
-			let data = ContextData {
-				variables: [(name, val)].into_iter().collect(),
-			};
-			self.ctx.push(Rc::new(RefCell::new(data)));
-			return;
+			self.ctx.push(Default::default());
 		}
 
 		let a = self
@@ -116,14 +199,46 @@ impl Context {
 			.next()
 			.expect("we JUST made sure at least one context window exists");
 		let mut a = a.borrow_mut();
+		if constant {
+			a.consts.insert(name.clone());
+		} else {
+			a.consts.remove(&name);
+		}
 		a.variables.insert(name, val);
 	}
 
-	pub fn resolve_reach(&self, r: &Reach) -> Result<IValue> {
+	/// declares the variables the pattern binds in the topmost context window \
+	/// errors if the value doesn't fit the pattern
+	pub fn set_pattern(&mut self, pattern: &Pattern, val: IValue) -> Result<()> {
+		self.declare_pattern(pattern, val, false)
+	}
+	/// like [Context::set_pattern], but the variables can't be changed afterwards
+	pub fn set_const_pattern(&mut self, pattern: &Pattern, val: IValue) -> Result<()> {
+		self.declare_pattern(pattern, val, true)
+	}
+	fn declare_pattern(&mut self, pattern: &Pattern, val: IValue, constant: bool) -> Result<()> {
+		if let Pattern::Binding(name) = pattern {
+			self.declare(name.clone(), val, constant);
+			return Ok(());
+		}
+		let mut bindings = Vec::new();
+		if !val.matches(pattern, &mut bindings) {
+			return Err(Error::PatternMismatch {
+				pattern: pattern.clone(),
+				val,
+			});
+		}
+		for (name, val) in bindings {
+			self.declare(name, val, constant);
+		}
+		Ok(())
+	}
+
+	pub fn resolve_reach(&self, r: &Reach) -> Result<IValue, Unwind> {
 		match r {
 			Reach::Value(val) => Ok(IValue::from_safe(val.clone(), self)),
 			Reach::Expr(expr) => self.resolve_expr(expr),
-			Reach::Named(name) => self.get_variable(name),
+			Reach::Named(name) => Ok(self.get_variable(name)?),
 
 			Reach::ArrayLiteral(arr) => {
 				let mut values = Vec::with_capacity(arr.len());
@@ -142,7 +257,7 @@ impl Context {
 			}
 		}
 	}
-	pub fn resolve_index(&self, index: IntoIndex) -> Result<Index> {
+	pub fn resolve_index(&self, index: IntoIndex) -> Result<Index, Unwind> {
 		match index {
 			IntoIndex::Index(i) => Ok(i),
 			IntoIndex::Expr(expr) => {
@@ -150,12 +265,100 @@ impl Context {
 				match val {
 					IValue::Value(Value::i32(i)) => Ok(Index::NumLit(i)),
 					IValue::Value(Value::String(s)) => Ok(Index::Ident(s)),
-					_ => Err(Error::InvalidExprFromIntoIndex(val)),
+					IValue::Range(range) => {
+						let (start, end) = range.positions();
+						Ok(Index::Slice(start, end))
+					}
+					_ => Err(Error::InvalidExprFromIntoIndex(val).into()),
+				}
+			}
+		}
+	}
+	/// resolves the function about to be called, along with the object it was taken from if it's
+	/// a method call (`obj.method(args)`), which the function gets to see as `self`
+	fn resolve_callee(&self, f: &Reach) -> Result<(IValue, Option<Receiver>), Unwind> {
+		match f {
+			Reach::Expr(expr) => self.resolve_callee_expr(expr),
+			f => Ok((self.resolve_reach(f)?, None)),
+		}
+	}
+	fn resolve_callee_expr(&self, expr: &Expr) -> Result<(IValue, Option<Receiver>), Unwind> {
+		match expr {
+			Expr::Reach(r) => self.resolve_callee(r),
+			Expr::Index(this, i) | Expr::OptionalIndex(this, i) => {
+				let (this, place) = self.resolve_place(this)?;
+				let optional = matches!(expr, Expr::OptionalIndex(..));
+				if optional && this.is_none() {
+					return Err(Unwind::Signal(Signal::ShortCircuit));
 				}
+				let i = self.resolve_index(i.clone())?;
+				match this.index(&i) {
+					Some(f) => Ok((
+						f,
+						Some(Receiver {
+							val: this,
+							place,
+							changed: false,
+						}),
+					)),
+					None if optional => Err(Unwind::Signal(Signal::ShortCircuit)),
+					None => Err(Error::InvalidIndex { a: this, i }.into()),
+				}
+			}
+			Expr::Spanned(span, expr) => {
+				self.resolve_callee_expr(expr).map_err(|err| err.at(*span))
 			}
+			expr => Ok((self.resolve_expr(expr)?, None)),
+		}
+	}
+	/// resolves `r`, along with the variable and the indexes it's stored at if it's something
+	/// that could be assigned to (`name.a.[i]`)
+	fn resolve_place(&self, r: &Reach) -> Result<(IValue, Option<Place>), Unwind> {
+		match r {
+			Reach::Named(name) => Ok((self.get_variable(name)?, Some((name.clone(), Vec::new())))),
+			Reach::Expr(expr) => self.resolve_place_expr(expr),
+			r => Ok((self.resolve_reach(r)?, None)),
 		}
 	}
-	pub fn resolve_expr(&self, expr: &Expr) -> Result<IValue> {
+	fn resolve_place_expr(&self, expr: &Expr) -> Result<(IValue, Option<Place>), Unwind> {
+		match expr {
+			Expr::Reach(r) => self.resolve_place(r),
+			Expr::Index(a, i) | Expr::OptionalIndex(a, i) => {
+				let (a, place) = self.resolve_place(a)?;
+				let optional = matches!(expr, Expr::OptionalIndex(..));
+				if optional && a.is_none() {
+					return Err(Unwind::Signal(Signal::ShortCircuit));
+				}
+				let i = self.resolve_index(i.clone())?;
+				let val = match a.index(&i) {
+					Some(val) => val,
+					None if optional => return Err(Unwind::Signal(Signal::ShortCircuit)),
+					None => return Err(Error::InvalidIndex { a, i }.into()),
+				};
+				let place = place.map(|(name, mut path)| {
+					path.push(i);
+					(name, path)
+				});
+				Ok((val, place))
+			}
+			Expr::Spanned(span, expr) => self.resolve_place_expr(expr).map_err(|err| err.at(*span)),
+			expr => Ok((self.resolve_expr(expr)?, None)),
+		}
+	}
+	/// puts the changed `self` of a method back where the receiver came from \
+	/// a receiver that isn't stored anywhere (like the object `make_obj()` returned) has nowhere
+	/// to go back to, so the change goes away with it
+	fn put_back(&self, this: Receiver) -> Result<()> {
+		match this.place {
+			_ if !this.changed => Ok(()),
+			Some((name, path)) if path.is_empty() => {
+				self.modify_variable(&name, this.val).map(drop)
+			}
+			Some((name, path)) => self.modify_path(&name, &path, this.val),
+			None => Ok(()),
+		}
+	}
+	pub fn resolve_expr(&self, expr: &Expr) -> Result<IValue, Unwind> {
 		match expr {
 			Expr::Reach(r) => self.resolve_reach(r),
 			Expr::Block(block) => {
@@ -165,20 +368,48 @@ impl Context {
 			Expr::Index(a, i) => {
 				let a = self.resolve_reach(a)?;
 				let i = self.resolve_index(i.clone())?;
-				a.index(&i)
-					.ok_or_else(|| Error::InvalidIndex { a, i: i.clone() })
+				Ok(a.index(&i)
+					.ok_or_else(|| Error::InvalidIndex { a, i: i.clone() })?)
 			}
-			Expr::Add(a, b) => {
+			Expr::OptionalIndex(a, i) => {
+				let a = self.resolve_reach(a)?;
+				if a.is_none() {
+					return Err(Unwind::Signal(Signal::ShortCircuit));
+				}
+				let i = self.resolve_index(i.clone())?;
+				a.index(&i).ok_or(Unwind::Signal(Signal::ShortCircuit))
+			}
+			Expr::OptionalChain(expr) => match self.resolve_expr(expr) {
+				Err(Unwind::Signal(Signal::ShortCircuit)) => Ok(IValue::None()),
+				out => out,
+			},
+			Expr::Add(a, b) => self.resolve_arith(ArithOp::Add, a, b),
+			Expr::Sub(a, b) => self.resolve_arith(ArithOp::Sub, a, b),
+			Expr::Neg(a) => {
 				let a = self.resolve_reach(a)?;
-				let b = self.resolve_reach(b)?;
 
-				a.add(&b).ok_or_else(|| Error::InvalidAddition { a, b })
+				a.neg().map_err(|err| match err {
+					ArithError::Overflow => Error::NegOverflow(a).into(),
+					_ => Error::InvalidNegation(a).into(),
+				})
 			}
-			Expr::Sub(a, b) => {
+			Expr::Mul(a, b) => self.resolve_arith(ArithOp::Mul, a, b),
+			Expr::Div(a, b) => self.resolve_arith(ArithOp::Div, a, b),
+			Expr::Rem(a, b) => self.resolve_arith(ArithOp::Rem, a, b),
+			Expr::Pow(a, b) => self.resolve_arith(ArithOp::Pow, a, b),
+			Expr::Range(a, b) | Expr::RangeInclusive(a, b) => {
 				let a = self.resolve_reach(a)?;
 				let b = self.resolve_reach(b)?;
 
-				a.sub(&b).ok_or_else(|| Error::InvalidSubtraction { a, b })
+				match (&a, &b) {
+					(IValue::Value(Value::i32(start)), IValue::Value(Value::i32(end))) => {
+						Ok(IValue::Range(match expr {
+							Expr::RangeInclusive(..) => Range::inclusive(*start, *end),
+							_ => Range::new(*start, *end),
+						}))
+					}
+					_ => Err(Error::InvalidRange { a, b }.into()),
+				}
 			}
 			Expr::Cmp(a, b) => {
 				let a = self.resolve_reach(a)?;
@@ -186,17 +417,35 @@ impl Context {
 
 				Ok(IValue::from_safe(Value::bool(a.custom_eq(&b)), self))
 			}
+			Expr::NotEq(a, b) => {
+				let a = self.resolve_reach(a)?;
+				let b = self.resolve_reach(b)?;
+
+				Ok(IValue::from_safe(Value::bool(!a.custom_eq(&b)), self))
+			}
 			Expr::Gt(a, b) => {
 				let a = self.resolve_reach(a)?;
 				let b = self.resolve_reach(b)?;
 
-				a.gt(&b).ok_or_else(|| Error::InvalidGt { a, b })
+				Ok(a.gt(&b).ok_or_else(|| Error::InvalidGt { a, b })?)
 			}
 			Expr::Lt(a, b) => {
 				let a = self.resolve_reach(a)?;
 				let b = self.resolve_reach(b)?;
 
-				a.lt(&b).ok_or_else(|| Error::InvalidLt { a, b })
+				Ok(a.lt(&b).ok_or_else(|| Error::InvalidLt { a, b })?)
+			}
+			Expr::Ge(a, b) => {
+				let a = self.resolve_reach(a)?;
+				let b = self.resolve_reach(b)?;
+
+				Ok(a.ge(&b).ok_or_else(|| Error::InvalidGe { a, b })?)
+			}
+			Expr::Le(a, b) => {
+				let a = self.resolve_reach(a)?;
+				let b = self.resolve_reach(b)?;
+
+				Ok(a.le(&b).ok_or_else(|| Error::InvalidLe { a, b })?)
 			}
 			Expr::Or(a, b) => {
 				let a = self.resolve_reach(a)?;
@@ -216,42 +465,61 @@ impl Context {
 					self,
 				))
 			}
-			Expr::CallFn { f, args } => {
-				let f = self.resolve_reach(f)?;
-				match f {
+			Expr::Coalesce(a, b) => {
+				let a = self.resolve_reach(a)?;
+				if a.is_none() {
+					self.resolve_reach(b)
+				} else {
+					Ok(a)
+				}
+			}
+			Expr::Not(a) => {
+				let a = self.resolve_reach(a)?;
+
+				Ok(IValue::from_safe(Value::bool(!a.is_true()), self))
+			}
+			Expr::Format(parts) => {
+				let mut out = String::new();
+				for part in parts {
+					let val = self.resolve_expr(part)?;
+					out += &format!("{val}");
+				}
+				Ok(IValue::String(out))
+			}
+			Expr::CallFn { f, args, named } => {
+				let (f, mut this) = self.resolve_callee(f)?;
+				let args = args
+					.iter()
+					.map(|a| self.resolve_reach(a))
+					.collect::<Result<Vec<_>, _>>()?;
+				let named = named
+					.iter()
+					.map(|(name, a)| Ok((name.clone(), self.resolve_reach(a)?)))
+					.collect::<Result<Vec<_>, Unwind>>()?;
+				let out = match f {
 					IValue::Value(Value::Function(f)) => {
 						eprintln!("calling a function, not a closure");
-						let args = args.clone().map(|reach| self.resolve_reach(&reach));
-						let args = if let Some(args) = args {
-							Some(args?)
-						} else {
-							None
-						};
-
-						self.call_fn(&f, args)
-					}
-					IValue::BuiltinFn(d) => {
-						let f = d.f();
-						let args = args.clone().map(|a| self.resolve_reach(&a));
-						let args = if let Some(args) = args {
-							args?
-						} else {
-							IValue::None()
-						};
-
-						f(args)
-					}
-					IValue::Closure(mut cl) => {
-						let args = args.clone().map(|a| self.resolve_reach(&a));
-						let args = if let Some(args) = args {
-							Some(args?)
-						} else {
-							None
-						};
-						cl.call(args)
+						self.call_fn(&f, this.as_mut(), args, named)
 					}
+					// builtins don't have parameter names
+					IValue::BuiltinFn(d) => match named.into_iter().next() {
+						Some((name, _)) => Err(Error::UnknownArg {
+							f: d.to_string(),
+							name,
+						}),
+						None => {
+							let f = d.f();
+							f(args)
+						}
+					},
+					IValue::Closure(mut cl) => cl.call_with(this.as_mut(), args, named),
 					_ => Err(Error::NotAFunction(f)),
+				};
+				let out = out?;
+				if let Some(this) = this {
+					self.put_back(this)?;
 				}
+				Ok(out)
 			}
 			Expr::Conditional {
 				condition,
@@ -267,119 +535,292 @@ impl Context {
 					Ok(if_false)
 				}
 			}
+			Expr::Match { value, arms } => {
+				let value = self.resolve_reach(value)?;
+				for arm in arms {
+					let mut bindings = Vec::new();
+					if !value.matches(&arm.pattern, &mut bindings) {
+						continue;
+					}
+
+					let mut window = self.push_window();
+					for (name, val) in bindings {
+						window.set_variable(name, val);
+					}
+					if let Some(guard) = &arm.guard
+						&& !window.resolve_expr(guard)?.is_true()
+					{
+						continue;
+					}
+					return window.resolve_expr(&arm.body);
+				}
+				Err(Error::NoMatchingArm(value).into())
+			}
+			Expr::Spanned(span, expr) => self.resolve_expr(expr).map_err(|err| err.at(*span)),
 		}
 	}
 
+	/// `a op b`
+	fn resolve_arith(&self, op: ArithOp, a: &Reach, b: &Reach) -> Result<IValue, Unwind> {
+		let a = self.resolve_reach(a)?;
+		let b = self.resolve_reach(b)?;
+		a.arith(op, &b)
+			.map_err(|err| Error::arith_op(err, op, a, b).into())
+	}
 	/// runs the given block as-is. does not isolate context at all so unless you wanna leak
 	/// internal variables you should probably use `context.clone().resolve_block()` \
 	///
-	/// if break is called it'll return Err(Error::Break) make sure to catch that
-	pub fn resolve_block(&mut self, block: &Block) -> Result<IValue> {
+	/// break, continue and return come out as [Unwind::Signal], make sure to catch those
+	pub fn resolve_block(&mut self, block: &Block) -> Result<IValue, Unwind> {
 		let len = block.0.len();
 		for (i, stmt) in block.iter().enumerate() {
 			let last = i == len - 1;
-			match stmt {
-				Statement::SetVariable(name, val) => {
-					let val = self.resolve_expr(val)?;
-					self.set_variable(name.clone(), val);
+			if let Some(val) = self.resolve_statement(stmt, last)? {
+				return Ok(val);
+			}
+		}
+		Ok(IValue::Value(Value::None))
+	}
+	/// runs a single statement, returning `Some` if the block it's in should return with that value
+	fn resolve_statement(
+		&mut self,
+		stmt: &Statement,
+		last: bool,
+	) -> Result<Option<IValue>, Unwind> {
+		match stmt {
+			Statement::SetVariable(pattern, val) => {
+				let val = self.resolve_expr(val)?;
+				self.set_pattern(pattern, val)?;
+			}
+			Statement::SetConst(pattern, val) => {
+				let val = self.resolve_expr(val)?;
+				self.set_const_pattern(pattern, val)?;
+			}
+			Statement::Import { path, names } => {
+				let mut module = self.import(path)?;
+				match names {
+					Imports::As(name) => self.set_const(name.clone(), IValue::Object(module)),
+					Imports::Only(names) => {
+						for name in names {
+							let Some(val) = module.remove(name) else {
+								return Err(Error::NotExported {
+									path: path.clone(),
+									name: name.clone(),
+								}
+								.into());
+							};
+							self.set_const(name.clone(), val);
+						}
+					}
 				}
-				Statement::ModifyVariable(name, val) => {
-					let val = self.resolve_expr(val)?;
+			}
+			Statement::ModifyVariable {
+				name,
+				path,
+				op,
+				expr,
+			} => {
+				// the indexes only get evaluated once, even if the old value gets read too
+				let path = path
+					.iter()
+					.map(|i| self.resolve_index(i.clone()))
+					.collect::<Result<Vec<_>, _>>()?;
+				let val = match op {
+					None => self.resolve_expr(expr)?,
+					Some(op) => {
+						let old = self.get_path(name, &path)?;
+						let rhs = self.resolve_expr(expr)?;
+						old.arith(*op, &rhs)
+							.map_err(|err| Error::arith_op(err, *op, old, rhs))?
+					}
+				};
+				if path.is_empty() {
 					self.modify_variable(name, val)?;
+				} else {
+					self.modify_path(name, &path, val)?;
 				}
-				Statement::Expr(expr) => {
-					let val = self.resolve_expr(expr)?;
-					if last {
-						return Ok(val);
-					}
+			}
+			Statement::Expr(expr) => {
+				let val = self.resolve_expr(expr)?;
+				if last {
+					return Ok(Some(val));
 				}
-				Statement::Return(expr) => {
-					let val = self.resolve_expr(expr)?;
-					return Ok(val);
+			}
+			Statement::Return(expr) => {
+				let val = self.resolve_expr(expr)?;
+				return Err(Unwind::Signal(Signal::Return(val)));
+			}
+
+			Statement::Loop { label, block } => while self.resolve_iteration(label, block)? {},
+			Statement::While {
+				label,
+				condition,
+				block,
+			} => {
+				while self.resolve_expr(condition)?.is_true() {
+					if !self.resolve_iteration(label, block)? {
+						break;
+					}
 				}
+			}
+			Statement::Break(label) => return Err(Unwind::Signal(Signal::Break(label.clone()))),
+			Statement::Continue(label) => {
+				return Err(Unwind::Signal(Signal::Continue(label.clone())));
+			}
+			Statement::LoopFor {
+				label,
+				pattern,
+				iter,
+				block,
+			} => {
+				let mut ctx = self.push_window();
 
-				Statement::Loop(block) => loop {
-					let out = self.resolve_block(block);
-					match out {
-						Err(Error::Break) => break,
-						a => a?,
-					};
-				},
-				Statement::Break => return Err(Error::Break),
-				Statement::LoopFor {
-					v_name,
-					iter,
-					block,
-				} => {
-					let v_name = v_name.clone();
-
-					let mut ctx = self.push_window();
-					ctx.set_variable(v_name.clone(), IValue::None());
-
-					let iter = self.resolve_expr(iter)?;
-					match iter {
-						IValue::Object(obj) => {
-							let next = obj
-								.get("next")
-								.cloned()
-								.ok_or_else(|| Error::ForNotAnIterator(IValue::Object(obj)))?;
-
-							let mut next = match next {
-								IValue::Closure(cl) => cl,
-								next => return Err(Error::ForNextIsntAClosure(next)),
-							};
+				let iter = self.resolve_expr(iter)?;
+				match iter {
+					IValue::Object(obj) => {
+						let Some(next) = obj.get("next").cloned() else {
+							return Err(Error::ForNotAnIterator(IValue::Object(obj)).into());
+						};
+						let mut this = Receiver::new(IValue::Object(obj));
 
-							loop {
-								let next = next.call(None)?;
-								match next {
-									IValue::Value(Value::None) => break,
-									val => {
-										// set the variable with the name requested to the value generated by the next fn
-										ctx.modify_variable(&v_name, val)?;
-										ctx.resolve_block(block)?;
+						let mut next = match next {
+							IValue::Closure(cl) => cl,
+							next => return Err(Error::ForNextIsntAClosure(next).into()),
+						};
+
+						loop {
+							let next = next.call_method(&mut this, vec![])?;
+							match next {
+								IValue::Value(Value::None) => break,
+								val => {
+									// set the variables the pattern binds to the value generated by the next fn
+									ctx.set_pattern(pattern, val)?;
+									if !ctx.resolve_iteration(label, block)? {
+										break;
 									}
 								}
 							}
 						}
-						IValue::Array(arr) => {
-							for next in arr {
-								// set the variable with the name requested to the value generated by the next fn
-								ctx.modify_variable(&v_name, next)?;
-								ctx.resolve_block(block)?;
+					}
+					IValue::Range(range) => {
+						for next in range.iter() {
+							ctx.set_pattern(pattern, IValue::i32(next))?;
+							if !ctx.resolve_iteration(label, block)? {
+								break;
 							}
 						}
-						val => return Err(Error::ForNotAnObject(val)),
-					};
-				}
+					}
+					IValue::Array(arr) => {
+						for next in arr {
+							// set the variables the pattern binds to the element
+							ctx.set_pattern(pattern, next)?;
+							if !ctx.resolve_iteration(label, block)? {
+								break;
+							}
+						}
+					}
+					val => return Err(Error::ForNotAnObject(val).into()),
+				};
+			}
 
-				Statement::DumpContext => {
-					println!("{}", self);
-				}
-				Statement::Pause => {
-					std::io::stdin().lines().next();
-				}
+			Statement::Error => return Err(Error::Unparsed.into()),
+
+			Statement::DumpContext => {
+				println!("{}", self);
+			}
+			Statement::Pause => {
+				std::io::stdin().lines().next();
+			}
+
+			Statement::Spanned(span, stmt) => {
+				return self
+					.resolve_statement(stmt, last)
+					.map_err(|err| err.at(*span));
 			}
 		}
-		Ok(IValue::Value(Value::None))
+		Ok(None)
+	}
+	/// runs the body of the loop with the given label once \
+	/// returns false if it got broken out of
+	fn resolve_iteration(&mut self, label: &Option<String>, block: &Block) -> Result<bool, Unwind> {
+		match self.resolve_block(block) {
+			Ok(_) => Ok(true),
+			Err(Unwind::Signal(signal)) if signal.is_for_loop(label) => {
+				Ok(matches!(signal, Signal::Continue(_)))
+			}
+			Err(err) => Err(err),
+		}
 	}
 
-	/// safely calls the given function
-	pub fn call_fn(&self, f: &Function, args: Option<IValue>) -> Result<IValue> {
-		let mut ctx = self.push_window();
-		match (&f.arg_name, args) {
-			(Some(name), Some(val)) => {
-				ctx.set_variable(name.clone(), val);
+	/// safely calls the given function, with `self` set to `this` if it's called as a method
+	/// (if the function changes `self`, `this` gets the changed version) \
+	/// parameters are set from the positional args first, then the `named` ones, and whatever's
+	/// left gets its default value \
+	/// errors if there are too many args, or an arg is missing or doesn't fit any parameter
+	pub fn call_fn(
+		&self,
+		f: &Function,
+		this: Option<&mut Receiver>,
+		args: Vec<IValue>,
+		mut named: Vec<(String, IValue)>,
+	) -> Result<IValue> {
+		if args.len() > f.params.len() {
+			return Err(Error::WrongArgCount {
+				f: f.to_string(),
+				expected: f.params.len(),
+				got: args.len(),
+			});
+		}
+		for (name, _) in &named {
+			match f.params.iter().position(|param| param.name == *name) {
+				None => {
+					return Err(Error::UnknownArg {
+						f: f.to_string(),
+						name: name.clone(),
+					});
+				}
+				Some(i) if i < args.len() => return Err(Error::ArgPassedTwice(name.clone())),
+				Some(_) => {}
 			}
-			(Some(name), None) => return Err(Error::MissingArg(name.clone())),
-			_ => {}
 		}
 
-		ctx.resolve_block(&f.block)
+		let mut ctx = self.push_window();
+		if let Some(this) = &this {
+			ctx.set_variable("self".into(), this.val.clone());
+		}
+		let mut args = args.into_iter();
+		for param in &f.params {
+			let by_name = named.iter().position(|(name, _)| *name == param.name);
+			let val = match (args.next(), by_name, &param.default) {
+				(Some(val), _, _) => val,
+				(None, Some(i), _) => named.swap_remove(i).1,
+				(None, None, Some(default)) => {
+					ctx.resolve_expr(default).map_err(Unwind::into_error)?
+				}
+				(None, None, None) => return Err(Error::MissingArg(param.name.clone())),
+			};
+			ctx.set_variable(param.name.clone(), val);
+		}
+
+		let out = match ctx.resolve_block(&f.block) {
+			Err(Unwind::Signal(Signal::Return(val))) => Ok(val),
+			out => out.map_err(Unwind::into_error),
+		}?;
+
+		let window = ctx.ctx.last().expect("we pushed a window for the call");
+		if let Some(this) = this
+			&& window.borrow().self_changed
+		{
+			this.val = ctx.get_variable("self")?;
+			this.changed = true;
+		}
+		Ok(out)
 	}
 
-	/// use for debugging only
+	/// runs the statements right in this context, so the variables they declare stick around
 	pub fn exec<I: IntoIterator<Item = Statement>>(&mut self, block: I) -> Result<IValue> {
 		self.resolve_block(&Block(block.into_iter().collect()))
+			.map_err(Unwind::into_error)
 	}
 }
 
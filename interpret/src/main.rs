@@ -1,22 +1,27 @@
 use interpret::{Context, IValue};
-use langlib::{Expr, Function, Reach, Statement, Value};
+use langlib::{Expr, Function, Pattern, Reach, Statement, Value};
 
 fn main() {
 	let out = Context::new::<IValue, _>([]).exec(
 		[
 			Statement::SetVariable(
-				"num".into(),
+				Pattern::Binding("num".into()),
 				Expr::Add(Reach::Value(Value::i32(2)), Reach::Value(Value::f32(4.2))),
 			),
 			Statement::SetVariable(
-				"grow".into(),
+				Pattern::Binding("grow".into()),
 				Expr::Reach(Reach::Value(Value::Function(Function::new(
-					None,
+					vec![],
 					[
-						Statement::ModifyVariable(
-							"num".into(),
-							Expr::Add(Reach::Named("num".into()), Reach::Value(Value::i32(2))),
-						),
+						Statement::ModifyVariable {
+							name: "num".into(),
+							path: vec![],
+							op: None,
+							expr: Expr::Add(
+								Reach::Named("num".into()),
+								Reach::Value(Value::i32(2)),
+							),
+						},
 						Statement::DumpContext,
 					],
 				)))),
@@ -29,7 +34,8 @@ fn main() {
 					Statement::Pause,
 					Statement::Expr(Expr::CallFn {
 						f: Reach::Named("grow".into()),
-						args: None,
+						args: vec![],
+						named: vec![],
 					}),
 				],
 				4,
@@ -0,0 +1,136 @@
+use std::{
+	cell::RefCell,
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+	rc::Rc,
+};
+
+use crate::*;
+
+/// turns the source code of a module into statements, or explains why it couldn't \
+/// interpret doesn't know how to parse code, so whoever runs it has to provide this
+pub type ParseFn = fn(&str) -> Result<Vec<Statement>, String>;
+
+/// runs the files that `import` statements import, shared between every module of a program \
+/// each module gets run only once, in its own [Context], and the variables it declares at the
+/// top level become an object that's handed to everyone importing it
+pub struct Loader {
+	parse: ParseFn,
+	/// the builtins every module starts out with
+	builtins: Vec<BuiltinFn>,
+	/// what the modules that finished running export, by their canonical path
+	done: RefCell<HashMap<PathBuf, HashMap<String, IValue>>>,
+	/// the modules that are running right now, in the order they got imported \
+	/// importing one of these again would be an import cycle
+	running: RefCell<Vec<PathBuf>>,
+}
+impl Loader {
+	pub fn new<I: IntoIterator<Item = BuiltinFn>>(parse: ParseFn, builtins: I) -> Rc<Self> {
+		Rc::new(Self {
+			parse,
+			builtins: builtins.into_iter().collect(),
+			done: Default::default(),
+			running: Default::default(),
+		})
+	}
+
+	/// runs the module at `path` (relative to `dir`) if it hasn't been run yet, returning what it
+	/// exports
+	fn load(self: &Rc<Self>, dir: &Path, path: &str) -> Result<HashMap<String, IValue>> {
+		let full = dir.join(path);
+		let read_err = |err: std::io::Error| Error::ImportRead {
+			path: path.into(),
+			err: err.to_string(),
+		};
+		let full = full.canonicalize().map_err(read_err)?;
+
+		if let Some(exports) = self.done.borrow().get(&full) {
+			return Ok(exports.clone());
+		}
+		if let Some(i) = self.running.borrow().iter().position(|p| *p == full) {
+			let cycle = self.running.borrow()[i..]
+				.iter()
+				.chain(std::iter::once(&full))
+				.map(|p| p.display().to_string())
+				.collect();
+			return Err(Error::ImportCycle(cycle));
+		}
+
+		let src = fs::read_to_string(&full).map_err(read_err)?;
+		let statements = (self.parse)(&src).map_err(|err| Error::ImportParse {
+			path: path.into(),
+			err,
+		})?;
+
+		let mut ctx = Context::default();
+		ctx.builtins(self.builtins.clone());
+		ctx.module = Some(Module {
+			loader: self.clone(),
+			dir: full.parent().map(Path::to_path_buf).unwrap_or_default(),
+		});
+
+		self.running.borrow_mut().push(full.clone());
+		let out = ctx.exec(statements);
+		self.running.borrow_mut().pop();
+		out.map_err(|err| Error::InModule {
+			path: path.into(),
+			err: Box::new(err),
+		})?;
+
+		let exports = ctx.exports();
+		self.done.borrow_mut().insert(full, exports.clone());
+		Ok(exports)
+	}
+}
+
+#[derive(Clone)]
+/// what a [Context] needs to run `import` statements
+pub struct Module {
+	loader: Rc<Loader>,
+	/// the folder the file being run is in, imports are relative to it
+	dir: PathBuf,
+}
+impl PartialEq for Module {
+	fn eq(&self, other: &Self) -> bool {
+		Rc::ptr_eq(&self.loader, &other.loader) && self.dir == other.dir
+	}
+}
+
+impl Context {
+	/// lets the code run in this context import other files \
+	/// `file` is the file that code is from: imports are relative to its folder, and it importing
+	/// itself (even through other modules) counts as a cycle \
+	/// without one (like in the repl), imports are relative to the working directory
+	pub fn imports(&mut self, loader: Rc<Loader>, file: Option<&Path>) {
+		let file = file.and_then(|file| file.canonicalize().ok());
+		let dir = match &file {
+			Some(file) => file.parent().map(Path::to_path_buf).unwrap_or_default(),
+			None => PathBuf::new(),
+		};
+		if let Some(file) = file {
+			loader.running.borrow_mut().push(file);
+		}
+		self.module = Some(Module { loader, dir });
+	}
+
+	/// runs the module at `path` (or gets it from the cache) and returns what it exports
+	pub fn import(&self, path: &str) -> Result<HashMap<String, IValue>> {
+		let Some(module) = &self.module else {
+			return Err(Error::ImportsUnavailable);
+		};
+		module.loader.load(&module.dir, path)
+	}
+
+	/// the variables declared at the top level, other than the builtins
+	fn exports(&self) -> HashMap<String, IValue> {
+		let Some(top) = self.ctx.first() else {
+			return HashMap::new();
+		};
+		let top = top.borrow();
+		let exports = top.variables.iter().filter(|(name, _)| *name != "builtins");
+		exports
+			.map(|(name, val)| (name.clone(), val.clone()))
+			.collect()
+	}
+}
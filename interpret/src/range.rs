@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// every whole number from `start` up to (but not including) `end`, made with `start..end`
+/// or `start..=end` \
+/// stored as i64s so `..=` can go all the way up to [i32::MAX], but every number in it is an i32
+pub struct Range {
+	start: i64,
+	end: i64,
+}
+impl Range {
+	/// `start..end`
+	pub fn new(start: i32, end: i32) -> Self {
+		Self {
+			start: start as i64,
+			end: end as i64,
+		}
+	}
+	/// `start..=end`
+	pub fn inclusive(start: i32, end: i32) -> Self {
+		Self {
+			start: start as i64,
+			end: end as i64 + 1,
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		(self.end - self.start).max(0) as usize
+	}
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+	/// the `i`th number in the range
+	pub fn get(&self, i: i32) -> Option<i32> {
+		let n = self.start + i as i64;
+		(i >= 0 && n < self.end).then_some(n as i32)
+	}
+	/// the part of the range from position `start` up to (but not including) position `end` \
+	/// positions past the end of the range get moved back to the end
+	pub fn slice(&self, start: usize, end: usize) -> Self {
+		let len = self.len();
+		let start = start.min(len);
+		let end = end.clamp(start, len);
+		Self {
+			start: self.start + start as i64,
+			end: self.start + end as i64,
+		}
+	}
+	/// the range as positions to slice something with (see [langlib::Index::Slice]), negative
+	/// numbers count as 0
+	pub fn positions(&self) -> (usize, usize) {
+		let position = |n: i64| n.max(0) as usize;
+		(position(self.start), position(self.end))
+	}
+	pub fn iter(&self) -> impl Iterator<Item = i32> + use<> {
+		(self.start..self.end).map(|n| n as i32)
+	}
+}
+impl Display for Range {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}..{}", self.start, self.end)
+	}
+}
@@ -3,9 +3,20 @@ use crate::*;
 pub fn len(val: &IValue) -> Option<usize> {
 	let len = match val {
 		IValue::Array(arr) => arr.len(),
+		IValue::Range(range) => range.len(),
 		IValue::Value(Value::Array(arr)) => arr.len(),
 		IValue::Value(Value::String(s)) => s.len(),
 		_ => return None,
 	};
 	Some(len)
 }
+
+/// unpacks the args passed to the builtin called `name`, erroring if there aren't exactly `N` of them
+pub fn args<const N: usize>(name: &str, args: Vec<IValue>) -> Result<[IValue; N]> {
+	let got = args.len();
+	args.try_into().map_err(|_| Error::WrongArgCount {
+		f: format!("[builtin {name}]"),
+		expected: N,
+		got,
+	})
+}
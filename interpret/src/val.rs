@@ -8,6 +8,8 @@ pub enum IValue {
 
 	Object(HashMap<String, IValue>),
 	Array(Vec<IValue>),
+	/// `start..end`, iterated over without ever making an array
+	Range(Range),
 
 	BuiltinFn(BuiltinFn),
 	Closure(Closure),
@@ -60,6 +62,9 @@ impl IValue {
 		IValue::Value(Value::None)
 	}
 
+	pub fn is_none(&self) -> bool {
+		matches!(self, IValue::Value(Value::None))
+	}
 	pub fn is_true(&self) -> bool {
 		match self {
 			IValue::Value(v) => v.is_true(),
@@ -74,6 +79,17 @@ impl IValue {
 			(IValue::Array(a), Index::NumLit(i)) => {
 				Some(a.iter().cloned().nth(*i as _).unwrap_or_else(IValue::None))
 			}
+			(IValue::Array(a), Index::Slice(start, end)) => {
+				let start = (*start).min(a.len());
+				let end = (*end).clamp(start, a.len());
+				Some(IValue::Array(a[start..end].to_vec()))
+			}
+			(IValue::Range(range), Index::NumLit(i)) => {
+				Some(range.get(*i).map(IValue::i32).unwrap_or_else(IValue::None))
+			}
+			(IValue::Range(range), Index::Slice(start, end)) => {
+				Some(IValue::Range(range.slice(*start, *end)))
+			}
 
 			_ => None,
 		};
@@ -90,20 +106,139 @@ impl IValue {
 		}
 	}
 
-	pub fn add(&self, rhs: &Self) -> Option<Self> {
+	/// sets the value at the end of `path`, changing the arrays and objects on the way in place \
+	/// objects get a new field if they don't have the last key yet, arrays don't grow
+	pub fn set_path(&mut self, path: &[Index], val: IValue) -> Result<()> {
+		let Some((i, rest)) = path.split_first() else {
+			*self = val;
+			return Ok(());
+		};
+		self.own_elements();
+
+		let inner = match (&mut *self, i) {
+			(IValue::Object(obj), i) => {
+				let key = i.clone().into_str();
+				if rest.is_empty() {
+					obj.insert(key, val);
+					return Ok(());
+				}
+				obj.get_mut(&key)
+			}
+			(IValue::Array(arr), Index::NumLit(n)) => {
+				usize::try_from(*n).ok().and_then(|n| arr.get_mut(n))
+			}
+			_ => None,
+		};
+		match inner {
+			Some(inner) => inner.set_path(rest, val),
+			None => Err(Error::InvalidIndexAssign {
+				a: self.clone(),
+				i: i.clone(),
+			}),
+		}
+	}
+	/// turns an array or object that's stored as a [Value] into an [IValue::Array] or
+	/// [IValue::Object], so its elements can be changed one by one
+	fn own_elements(&mut self) {
+		match self {
+			IValue::Value(Value::Array(arr)) => {
+				let arr = std::mem::take(arr);
+				*self = IValue::Array(arr.into_iter().map(IValue::Value).collect());
+			}
+			IValue::Value(Value::Object(obj)) => {
+				let obj = std::mem::take(obj);
+				*self = IValue::Object(
+					obj.into_iter()
+						.map(|(k, v)| (k, IValue::Value(v)))
+						.collect(),
+				);
+			}
+			_ => {}
+		}
+	}
+
+	pub fn add(&self, rhs: &Self) -> Result<Self, ArithError> {
 		match (self, rhs) {
 			(IValue::Value(a), IValue::Value(b)) => a.add(b).map(IValue::Value),
 			(IValue::Array(a), IValue::Array(b)) => {
-				Some(IValue::Array(a.iter().chain(b.iter()).cloned().collect()))
+				Ok(IValue::Array(a.iter().chain(b.iter()).cloned().collect()))
 			}
-			_ => None,
+			_ => Err(ArithError::Invalid),
 		}
 	}
-	pub fn sub(&self, rhs: &Self) -> Option<Self> {
+	pub fn sub(&self, rhs: &Self) -> Result<Self, ArithError> {
 		match (self, rhs) {
 			// this match statement contains every subtraction operation that's legal
 			(IValue::Value(a), IValue::Value(b)) => a.sub(b).map(IValue::Value),
-			_ => None,
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	pub fn neg(&self) -> Result<Self, ArithError> {
+		match self {
+			IValue::Value(a) => a.neg().map(IValue::Value),
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	pub fn mul(&self, rhs: &Self) -> Result<Self, ArithError> {
+		match (self, rhs) {
+			(IValue::Value(a), IValue::Value(b)) => a.mul(b).map(IValue::Value),
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	pub fn div(&self, rhs: &Self) -> Result<Self, ArithError> {
+		match (self, rhs) {
+			(IValue::Value(a), IValue::Value(b)) => a.div(b).map(IValue::Value),
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	pub fn rem(&self, rhs: &Self) -> Result<Self, ArithError> {
+		match (self, rhs) {
+			(IValue::Value(a), IValue::Value(b)) => a.rem(b).map(IValue::Value),
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	pub fn pow(&self, rhs: &Self) -> Result<Self, ArithError> {
+		match (self, rhs) {
+			(IValue::Value(a), IValue::Value(b)) => a.pow(b).map(IValue::Value),
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	/// applies `op` to the two values, `a.arith(ArithOp::Add, b)` is `a + b`
+	pub fn arith(&self, op: ArithOp, rhs: &Self) -> Result<Self, ArithError> {
+		match op {
+			ArithOp::Add => self.add(rhs),
+			ArithOp::Sub => self.sub(rhs),
+			ArithOp::Mul => self.mul(rhs),
+			ArithOp::Div => self.div(rhs),
+			ArithOp::Rem => self.rem(rhs),
+			ArithOp::Pow => self.pow(rhs),
+		}
+	}
+	/// true if the value fits the pattern, in which case the values the pattern binds get added to `bindings`
+	pub fn matches(&self, pattern: &Pattern, bindings: &mut Vec<(String, IValue)>) -> bool {
+		match (pattern, self) {
+			(Pattern::Wildcard, _) => true,
+			(Pattern::Binding(name), val) => {
+				bindings.push((name.clone(), val.clone()));
+				true
+			}
+			(Pattern::Literal(lit), val) => IValue::Value(lit.clone()).custom_eq(val),
+
+			(Pattern::Array(items), IValue::Array(arr)) => match_array(items, arr, bindings),
+			(Pattern::Array(items), IValue::Value(Value::Array(arr))) => {
+				let arr: Vec<_> = arr.iter().cloned().map(IValue::Value).collect();
+				match_array(items, &arr, bindings)
+			}
+			(
+				Pattern::Object(fields),
+				val @ (IValue::Object(_) | IValue::Value(Value::Object(_))),
+			) => fields
+				.iter()
+				.all(|(key, p)| match val.index(&Index::Ident(key.clone())) {
+					Some(field) => field.matches(p, bindings),
+					None => false,
+				}),
+			_ => false,
 		}
 	}
 	/// basically just PartialEq except it's lenient if it's the same but a different number type
@@ -127,31 +262,78 @@ impl IValue {
 			_ => None,
 		}
 	}
+	pub fn ge(&self, rhs: &Self) -> Option<Self> {
+		match (self, rhs) {
+			// this match statement contains every ge operation that's legal
+			(IValue::Value(a), IValue::Value(b)) => a.ge(b).map(IValue::Value),
+			_ => None,
+		}
+	}
+	pub fn le(&self, rhs: &Self) -> Option<Self> {
+		match (self, rhs) {
+			// this match statement contains every le operation that's legal
+			(IValue::Value(a), IValue::Value(b)) => a.le(b).map(IValue::Value),
+			_ => None,
+		}
+	}
 }
 impl Display for IValue {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
-			IValue::Value(value) => write!(f, "{value}"),
+			IValue::Value(value) => Display::fmt(value, f),
 			IValue::Object(hash_map) => {
 				write!(f, "obj {{")?;
 				for (k, v) in hash_map {
-					write!(f, " {k}: {v} ")?;
+					write!(f, " {k}: {v:#} ")?;
 				}
 				write!(f, "}}")
 			}
 			IValue::Array(ivalues) => {
 				write!(f, "[")?;
 				for val in ivalues {
-					write!(f, " {val}")?;
+					write!(f, " {val:#}")?;
 				}
 				write!(f, " ]")
 			}
+			IValue::Range(range) => write!(f, "{range}"),
 			IValue::BuiltinFn(dyn_builtin) => write!(f, "{dyn_builtin}"),
 			IValue::Closure(closure) => write!(f, "{}", closure.f()),
 		}
 	}
 }
 
+/// matches the elements of an array against the patterns in an array pattern \
+/// a [Pattern::Rest] takes however many elements are left over after the rest of the patterns
+fn match_array(items: &[Pattern], arr: &[IValue], bindings: &mut Vec<(String, IValue)>) -> bool {
+	let rest = items.iter().position(|p| matches!(p, Pattern::Rest(_)));
+	let Some(rest) = rest else {
+		return items.len() == arr.len()
+			&& items
+				.iter()
+				.zip(arr)
+				.all(|(p, val)| val.matches(p, bindings));
+	};
+	if arr.len() < items.len() - 1 {
+		return false;
+	}
+
+	let (before, after) = (&items[..rest], &items[rest + 1..]);
+	let (head, tail) = arr.split_at(arr.len() - after.len());
+	let (head, middle) = head.split_at(before.len());
+	let fits = before
+		.iter()
+		.zip(head)
+		.all(|(p, val)| val.matches(p, bindings))
+		&& after
+			.iter()
+			.zip(tail)
+			.all(|(p, val)| val.matches(p, bindings));
+	if let Pattern::Rest(Some(name)) = &items[rest] {
+		bindings.push((name.clone(), IValue::Array(middle.to_vec())));
+	}
+	fits
+}
+
 use std::borrow::Cow;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -160,7 +342,7 @@ pub struct BuiltinFn {
 	id: u64,
 	name: Cow<'static, str>,
 
-	f: fn(IValue) -> Result<IValue>,
+	f: fn(Vec<IValue>) -> Result<IValue>,
 }
 impl BuiltinFn {
 	pub fn id(&self) -> u64 {
@@ -169,7 +351,7 @@ impl BuiltinFn {
 	pub fn name(&self) -> &Cow<'static, str> {
 		&self.name
 	}
-	pub fn f(&self) -> fn(IValue) -> Result<IValue> {
+	pub fn f(&self) -> fn(Vec<IValue>) -> Result<IValue> {
 		self.f
 	}
 }
@@ -195,7 +377,7 @@ impl BuiltinBuilder {
 	pub fn new_fn(
 		&mut self,
 		name: impl Into<Cow<'static, str>>,
-		f: fn(IValue) -> Result<IValue>,
+		f: fn(Vec<IValue>) -> Result<IValue>,
 	) -> BuiltinFn {
 		let builtin = BuiltinFn {
 			id: self.id,
@@ -227,7 +409,20 @@ impl Closure {
 		&self.f
 	}
 
-	pub fn call(&mut self, args: Option<IValue>) -> Result<IValue> {
-		self.ctx.call_fn(&self.f, args)
+	pub fn call(&mut self, args: Vec<IValue>) -> Result<IValue> {
+		self.call_with(None, args, Vec::new())
+	}
+	/// calls the closure with `self` set to `this`
+	pub fn call_method(&mut self, this: &mut Receiver, args: Vec<IValue>) -> Result<IValue> {
+		self.call_with(Some(this), args, Vec::new())
+	}
+	/// see [Context::call_fn]
+	pub fn call_with(
+		&mut self,
+		this: Option<&mut Receiver>,
+		args: Vec<IValue>,
+		named: Vec<(String, IValue)>,
+	) -> Result<IValue> {
+		self.ctx.call_fn(&self.f, this, args, named)
 	}
 }
@@ -2,28 +2,99 @@ use crate::*;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Statement {
-	ModifyVariable(String, Expr),
-	SetVariable(String, Expr),
-	/// doesn't really behave like you'd expect to but it's fine (there's literally no way
-	/// to even make an explicit return statement if using basicparse so it's fine)
+	/// `name = expr`, or `name.a.[i] = expr` to change just a part of what's in the variable
+	ModifyVariable {
+		name: String,
+		/// the indexes leading to the part of the variable that gets changed, empty if it's all of it
+		path: Vec<IntoIndex>,
+		/// the operator of a compound assignment (`+` for `name += expr`), the new value is the old
+		/// one and expr put together with it
+		op: Option<ArithOp>,
+		expr: Expr,
+	},
+	/// `let pattern = expr`, declares every variable the pattern binds
+	SetVariable(Pattern, Expr),
+	/// `const pattern = expr`, like [Statement::SetVariable] but the variables can't be changed
+	/// afterwards
+	SetConst(Pattern, Expr),
+	/// `import "path.dl" as name` or `import { a b } from "path.dl"`, runs the file (only the first
+	/// time it's imported) and binds what it exports
+	Import { path: String, names: Imports },
+	/// returns the value of the expr from the function it's in, no matter how many blocks
+	/// and loops deep it is
 	Return(Expr),
 	/// executes the expr and does nothing with the output value \
 	/// UNLESS! if it's the last instruction in a block, then it gets returned
 	Expr(Expr),
 
 	/// loops over the given block until broken out with break
-	Loop(Block),
-	Break,
+	Loop {
+		/// `'label: loop {}`, so `break 'label` can break out of it from an inner loop
+		label: Option<String>,
+		block: Block,
+	},
+	/// runs the block over and over as long as the condition is true
+	While {
+		label: Option<String>,
+		condition: Expr,
+		block: Block,
+	},
 	LoopFor {
-		v_name: String,
+		label: Option<String>,
+		/// what each element gets bound to, usually just a name
+		pattern: Pattern,
 		iter: Expr,
 		block: Block,
 	},
+	/// stops the innermost loop, or the loop with the given label
+	Break(Option<String>),
+	/// skips to the next iteration of the innermost loop, or the loop with the given label
+	Continue(Option<String>),
+
+	/// a statement that couldn't be parsed, left in its place by a parser that keeps going after errors
+	Error,
 
 	/// debug
 	DumpContext,
 	/// debug, pauses execution until a key is pressed
 	Pause,
+
+	/// the wrapped statement, along with where it was in the source code
+	Spanned(Span, Box<Statement>),
+}
+impl Statement {
+	pub fn spanned(self, span: Span) -> Self {
+		match self {
+			Self::Spanned(_, stmt) => Self::Spanned(span, stmt),
+			stmt => Self::Spanned(span, Box::new(stmt)),
+		}
+	}
+	pub fn span(&self) -> Option<Span> {
+		match self {
+			Self::Spanned(span, _) => Some(*span),
+			_ => None,
+		}
+	}
+	pub fn unspanned(&self) -> &Statement {
+		match self {
+			Self::Spanned(_, stmt) => stmt.unspanned(),
+			stmt => stmt,
+		}
+	}
+	pub fn into_unspanned(self) -> Statement {
+		match self {
+			Self::Spanned(_, stmt) => stmt.into_unspanned(),
+			stmt => stmt,
+		}
+	}
+}
+#[derive(Clone, Debug, PartialEq)]
+/// what an [Statement::Import] binds
+pub enum Imports {
+	/// `as name`, the whole module as an object
+	As(String),
+	/// `{ a b }`, just these bindings from the module
+	Only(Vec<String>),
 }
 #[derive(Clone, Debug, PartialEq)]
 pub struct Block(pub Vec<Statement>);
@@ -34,20 +105,53 @@ impl Block {
 }
 
 #[derive(Clone, Debug, PartialEq)]
-/// functions don't have args for now lol
 pub struct Function {
-	/// the arg name (if any)
-	pub arg_name: Option<String>,
+	/// the parameters, in the order the args get passed in
+	pub params: Vec<Param>,
 	pub block: Block,
 }
 impl Function {
-	pub fn new<I: IntoIterator<Item = Statement>>(arg_name: Option<String>, statements: I) -> Self {
+	/// a function without any default values for its parameters
+	pub fn new<I: IntoIterator<Item = Statement>>(params: Vec<String>, statements: I) -> Self {
+		let params = params.into_iter().map(Param::new).collect();
 		let block = Block(statements.into_iter().collect());
-		Self { arg_name, block }
+		Self { params, block }
 	}
 }
 impl Display for Function {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "fn({})", self.arg_name.as_deref().unwrap_or(""))
+		write!(f, "fn(")?;
+		for (i, param) in self.params.iter().enumerate() {
+			if i != 0 {
+				write!(f, ", ")?;
+			}
+			write!(f, "{param}")?;
+		}
+		write!(f, ")")
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// a parameter of a [Function], `name` or `name = default`
+pub struct Param {
+	pub name: String,
+	/// what the parameter gets set to if the call doesn't pass it, evaluated on every call
+	/// (after the parameters before it are set, so it can use them)
+	pub default: Option<Expr>,
+}
+impl Param {
+	pub fn new(name: String) -> Self {
+		Self {
+			name,
+			default: None,
+		}
+	}
+}
+impl Display for Param {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.default {
+			Some(_) => write!(f, "{} = ..", self.name),
+			None => write!(f, "{}", self.name),
+		}
 	}
 }
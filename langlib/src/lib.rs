@@ -3,6 +3,12 @@ use std::{collections::HashMap, fmt::Display};
 
 pub use func::*;
 
+mod span;
+pub use span::*;
+
+mod pattern;
+pub use pattern::*;
+
 #[derive(Clone, Debug, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum Value {
@@ -39,37 +45,105 @@ impl Value {
 		}
 	}
 
-	pub fn add(&self, rhs: &Self) -> Option<Self> {
+	pub fn add(&self, rhs: &Self) -> Result<Self, ArithError> {
 		match (self, rhs) {
 			// this match statement contains every addition operation that's legal
-			(Value::i32(a), Value::i32(b)) => Some(Self::i32(*a + *b)),
-			(Value::f32(a), Value::f32(b)) => Some(Self::f32(*a + *b)),
-			(Value::i32(a), Value::f32(b)) => Some(Self::f32(*a as f32 + *b)),
-			(Value::f32(a), Value::i32(b)) => Some(Self::f32(*a + *b as f32)),
-			(Value::String(a), Value::String(b)) => Some(Self::String(format!("{a}{b}"))),
+			(Value::i32(a), Value::i32(b)) => checked(a.checked_add(*b)),
+			(Value::f32(a), Value::f32(b)) => Ok(Self::f32(*a + *b)),
+			(Value::i32(a), Value::f32(b)) => Ok(Self::f32(*a as f32 + *b)),
+			(Value::f32(a), Value::i32(b)) => Ok(Self::f32(*a + *b as f32)),
+			(Value::String(a), Value::String(b)) => Ok(Self::String(format!("{a}{b}"))),
 
-			(Value::Array(a), Value::Array(b)) => Some(Self::Array(
+			(Value::Array(a), Value::Array(b)) => Ok(Self::Array(
 				a.iter().cloned().chain(b.iter().cloned()).collect(), // a then b
 			)),
 
-			(a, Value::None) | (Value::None, a) => Some(a.clone()),
+			(a, Value::None) | (Value::None, a) => Ok(a.clone()),
 
-			_ => None,
+			_ => Err(ArithError::Invalid),
 		}
 	}
-	pub fn sub(&self, rhs: &Self) -> Option<Self> {
+	pub fn sub(&self, rhs: &Self) -> Result<Self, ArithError> {
 		match (self, rhs) {
 			// this match statement contains every subtraction operation that's legal
-			(Value::i32(a), Value::i32(b)) => Some(Self::i32(*a - *b)),
-			(Value::f32(a), Value::f32(b)) => Some(Self::f32(*a - *b)),
-			(Value::i32(a), Value::f32(b)) => Some(Self::f32(*a as f32 - *b)),
-			(Value::f32(a), Value::i32(b)) => Some(Self::f32(*a - *b as f32)),
+			(Value::i32(a), Value::i32(b)) => checked(a.checked_sub(*b)),
+			(Value::f32(a), Value::f32(b)) => Ok(Self::f32(*a - *b)),
+			(Value::i32(a), Value::f32(b)) => Ok(Self::f32(*a as f32 - *b)),
+			(Value::f32(a), Value::i32(b)) => Ok(Self::f32(*a - *b as f32)),
 
-			(Value::None, a) => Value::i32(0).sub(a),
-			(a, Value::None) => Some(a.clone()),
+			(a, Value::None) => Ok(a.clone()),
 
-			_ => None,
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	pub fn neg(&self) -> Result<Self, ArithError> {
+		match self {
+			Value::i32(a) => checked(a.checked_neg()),
+			Value::f32(a) => Ok(Self::f32(-*a)),
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	pub fn mul(&self, rhs: &Self) -> Result<Self, ArithError> {
+		match (self, rhs) {
+			// this match statement contains every multiplication operation that's legal
+			(Value::i32(a), Value::i32(b)) => checked(a.checked_mul(*b)),
+			(Value::f32(a), Value::f32(b)) => Ok(Self::f32(*a * *b)),
+			(Value::i32(a), Value::f32(b)) => Ok(Self::f32(*a as f32 * *b)),
+			(Value::f32(a), Value::i32(b)) => Ok(Self::f32(*a * *b as f32)),
+
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	/// dividing two integers rounds towards zero, like it does in rust
+	pub fn div(&self, rhs: &Self) -> Result<Self, ArithError> {
+		if rhs.is_zero() && self.is_number() {
+			return Err(ArithError::DivisionByZero);
+		}
+		match (self, rhs) {
+			// this match statement contains every division operation that's legal
+			(Value::i32(a), Value::i32(b)) => checked(a.checked_div(*b)),
+			(Value::f32(a), Value::f32(b)) => Ok(Self::f32(*a / *b)),
+			(Value::i32(a), Value::f32(b)) => Ok(Self::f32(*a as f32 / *b)),
+			(Value::f32(a), Value::i32(b)) => Ok(Self::f32(*a / *b as f32)),
+
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	/// the remainder has the same sign as the left side, like it does in rust
+	pub fn rem(&self, rhs: &Self) -> Result<Self, ArithError> {
+		if rhs.is_zero() && self.is_number() {
+			return Err(ArithError::DivisionByZero);
 		}
+		match (self, rhs) {
+			// this match statement contains every remainder operation that's legal
+			(Value::i32(a), Value::i32(b)) => checked(a.checked_rem(*b)),
+			(Value::f32(a), Value::f32(b)) => Ok(Self::f32(*a % *b)),
+			(Value::i32(a), Value::f32(b)) => Ok(Self::f32(*a as f32 % *b)),
+			(Value::f32(a), Value::i32(b)) => Ok(Self::f32(*a % *b as f32)),
+
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	/// an integer to the power of a negative integer is a float, since it's a fraction
+	pub fn pow(&self, rhs: &Self) -> Result<Self, ArithError> {
+		match (self, rhs) {
+			// this match statement contains every power operation that's legal
+			(Value::i32(a), Value::i32(b)) => match u32::try_from(*b) {
+				Ok(b) => checked(a.checked_pow(b)),
+				Err(_) => Ok(Self::f32((*a as f32).powi(*b))),
+			},
+			(Value::f32(a), Value::f32(b)) => Ok(Self::f32(a.powf(*b))),
+			(Value::i32(a), Value::f32(b)) => Ok(Self::f32((*a as f32).powf(*b))),
+			(Value::f32(a), Value::i32(b)) => Ok(Self::f32(a.powi(*b))),
+
+			_ => Err(ArithError::Invalid),
+		}
+	}
+	fn is_number(&self) -> bool {
+		matches!(self, Value::i32(_) | Value::f32(_))
+	}
+	fn is_zero(&self) -> bool {
+		matches!(self, Value::i32(0)) || matches!(self, Value::f32(a) if *a == 0.0)
 	}
 	/// basically just PartialEq except it's lenient if it's the same but a different number type
 	pub fn custom_eq(&self, rhs: &Self) -> bool {
@@ -108,25 +182,97 @@ impl Value {
 			_ => None,
 		}
 	}
+	pub fn ge(&self, rhs: &Self) -> Option<Self> {
+		match (self, rhs) {
+			// this match statement contains every ge operation that's legal
+			(Value::i32(a), Value::i32(b)) => Some(Self::bool(*a >= *b)),
+			(Value::f32(a), Value::f32(b)) => Some(Self::bool(*a >= *b)),
+			(Value::i32(a), Value::f32(b)) => Some(Self::bool(*a as f32 >= *b)),
+			(Value::f32(a), Value::i32(b)) => Some(Self::bool(*a >= *b as f32)),
+
+			(Value::None, Value::None) => Some(Self::bool(true)),
+			(Value::None, _) => Some(Self::bool(false)),
+			(_, Value::None) => Some(Self::bool(true)),
+
+			_ => None,
+		}
+	}
+	pub fn le(&self, rhs: &Self) -> Option<Self> {
+		match (self, rhs) {
+			// this match statement contains every le operation that's legal
+			(Value::i32(a), Value::i32(b)) => Some(Self::bool(*a <= *b)),
+			(Value::f32(a), Value::f32(b)) => Some(Self::bool(*a <= *b)),
+			(Value::i32(a), Value::f32(b)) => Some(Self::bool((*a as f32) <= (*b))),
+			(Value::f32(a), Value::i32(b)) => Some(Self::bool(*a <= *b as f32)),
+
+			(Value::None, Value::None) => Some(Self::bool(true)),
+			(Value::None, _) => Some(Self::bool(true)),
+			(_, Value::None) => Some(Self::bool(false)),
+
+			_ => None,
+		}
+	}
+}
+/// why an arithmetic operation on two [Value]s failed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArithError {
+	/// the operation doesn't make sense for these types, like `"a" * [1]`
+	Invalid,
+	/// dividing by zero, or taking the remainder of a division by zero
+	DivisionByZero,
+	/// the result doesn't fit in an i32
+	Overflow,
+}
+/// an operation on two values that does arithmetic, what the compound assignments (`+=`, `-=`...)
+/// apply to the old value and the new one
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArithOp {
+	Add,
+	Sub,
+	Mul,
+	Div,
+	Rem,
+	Pow,
+}
+impl ArithOp {
+	/// how it's written in code, `+` for [ArithOp::Add]
+	pub fn symbol(self) -> &'static str {
+		match self {
+			Self::Add => "+",
+			Self::Sub => "-",
+			Self::Mul => "*",
+			Self::Div => "/",
+			Self::Rem => "%",
+			Self::Pow => "**",
+		}
+	}
+}
+/// the result of an integer operation, [ArithError::Overflow] if it's [None]
+fn checked(a: Option<i32>) -> Result<Value, ArithError> {
+	a.map(Value::i32).ok_or(ArithError::Overflow)
 }
+
+/// strings are displayed as-is, unless formatted with `{:#}` (which is what arrays and
+/// objects do with their elements), in which case they're quoted like a string literal
 impl Display for Value {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Value::bool(b) => write!(f, "{b}"),
 			Value::i32(a) => write!(f, "{a}"),
 			Value::f32(a) => write!(f, "{a}"),
-			Value::String(a) => write!(f, "{a:?}"),
+			Value::String(a) if f.alternate() => write!(f, "{}", quote(a)),
+			Value::String(a) => write!(f, "{a}"),
 			Value::Object(hash_map) => {
 				write!(f, "obj {{")?;
 				for (k, v) in hash_map {
-					write!(f, "  {k}: {v}")?;
+					write!(f, "  {k}: {v:#}")?;
 				}
 				write!(f, "}}")
 			}
 			Value::Array(ivalues) => {
 				write!(f, "[")?;
 				for val in ivalues {
-					write!(f, " {val}")?;
+					write!(f, " {val:#}")?;
 				}
 				write!(f, " ]")
 			}
@@ -136,16 +282,42 @@ impl Display for Value {
 	}
 }
 
+/// the string as it'd be written as a string literal, quotes included
+pub fn quote(s: &str) -> String {
+	let mut out = String::with_capacity(s.len() + 2);
+	out.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\t' => out.push_str("\\t"),
+			'\r' => out.push_str("\\r"),
+			'\0' => out.push_str("\\0"),
+			c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+	out
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Index {
 	Ident(String),
 	NumLit(i32),
+	/// the elements from position `.0` up to (but not including) `.1`, from indexing with a range
+	/// (`arr.[1..3]`) \
+	/// negative ends of the range become 0 and ends past the end get moved back to it, so slicing
+	/// never fails, it just gives less back
+	Slice(usize, usize),
 }
 impl Index {
 	pub fn into_str(self) -> String {
 		match self {
 			Index::Ident(name) => name,
 			Index::NumLit(num) => format!("{num}"),
+			Index::Slice(start, end) => format!("{start}..{end}"),
 		}
 	}
 }
@@ -165,33 +337,74 @@ pub enum Expr {
 	/// so this is used for reaching into maps (like person.name)
 	/// and arrays too (like array.0)
 	Index(Reach, IntoIndex),
+	/// `a?.b`, like [Expr::Index] but if a is None or doesn't have b, the [Expr::OptionalChain]
+	/// it's in stops right there and gives None
+	OptionalIndex(Reach, IntoIndex),
+	/// a chain of indexing and calls with at least one `?.` in it (`a?.b.c()`), None if one of
+	/// the `?.`s cut it short
+	OptionalChain(Box<Expr>),
 
 	// these return bools
 	Cmp(Reach, Reach),
+	// true if they're not equal
+	NotEq(Reach, Reach),
 	// true if the first is larger
 	Gt(Reach, Reach),
 	// true if the second is larger
 	Lt(Reach, Reach),
+	// true if the first is larger or they're equal
+	Ge(Reach, Reach),
+	// true if the second is larger or they're equal
+	Le(Reach, Reach),
 
 	// a or b
 	Or(Reach, Reach),
 	// a and b
 	And(Reach, Reach),
+	/// a ?? b, b if a is None, a otherwise. b only gets evaluated if it's needed
+	Coalesce(Reach, Reach),
+	// true if a isn't true
+	Not(Reach),
 
 	Conditional {
 		condition: Reach,
 		if_true: Reach,
 		if_false: Reach,
 	},
+	/// evaluates the body of the first arm whose pattern matches the value
+	Match {
+		value: Reach,
+		arms: Vec<MatchArm>,
+	},
 
 	// these return one of the types they are passed
 	Add(Reach, Reach),
 	Sub(Reach, Reach),
-	/// calls the given function. no args for now
+	/// -a
+	Neg(Reach),
+	Mul(Reach, Reach),
+	Div(Reach, Reach),
+	/// a % b
+	Rem(Reach, Reach),
+	/// a ** b
+	Pow(Reach, Reach),
+	/// a..b, every whole number from a up to (but not including) b
+	Range(Reach, Reach),
+	/// a..=b, every whole number from a up to and including b
+	RangeInclusive(Reach, Reach),
+	/// displays every expr (the same way `to_string` does) and joins them into one string
+	Format(Vec<Expr>),
+
+	/// calls the given function with the given args
 	CallFn {
 		f: Reach,
-		args: Option<Reach>,
+		args: Vec<Reach>,
+		/// args passed by the name of the parameter (`f(mode: "w")`), after the positional ones
+		named: Vec<(String, Reach)>,
 	},
+
+	/// the wrapped expr, along with where it was in the source code
+	Spanned(Span, Box<Expr>),
 }
 impl Expr {
 	pub fn into_reach(self) -> Reach {
@@ -200,6 +413,34 @@ impl Expr {
 			e => Reach::Expr(Box::new(e)),
 		}
 	}
+
+	pub fn spanned(self, span: Span) -> Self {
+		match self {
+			Self::Spanned(_, expr) => Self::Spanned(span, expr),
+			expr => Self::Spanned(span, Box::new(expr)),
+		}
+	}
+	pub fn span(&self) -> Option<Span> {
+		match self {
+			Self::Spanned(span, _) => Some(*span),
+			_ => None,
+		}
+	}
+	/// strips every layer of [Expr::Spanned] (and reaches that are just a wrapped expr)
+	pub fn unspanned(&self) -> &Expr {
+		match self {
+			Self::Spanned(_, expr) => expr.unspanned(),
+			Self::Reach(Reach::Expr(expr)) => expr.unspanned(),
+			expr => expr,
+		}
+	}
+	pub fn into_unspanned(self) -> Expr {
+		match self {
+			Self::Spanned(_, expr) => expr.into_unspanned(),
+			Self::Reach(Reach::Expr(expr)) => expr.into_unspanned(),
+			expr => expr,
+		}
+	}
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -0,0 +1,62 @@
+use crate::*;
+
+#[derive(Clone, Debug, PartialEq)]
+/// describes the shape of a value, and which parts of it get bound to which names \
+/// used by `match` arms, `let` and `for` loops
+pub enum Pattern {
+	/// `_`, matches anything
+	Wildcard,
+	/// a name, matches anything and binds it to that name
+	Binding(String),
+	/// a number or string literal or `None`, matches values equal to it
+	Literal(Value),
+	/// `[a b c]`, matches arrays with exactly as many elements, if each of them matches
+	Array(Vec<Pattern>),
+	/// `..name` (or just `..`) inside of an array pattern, matches however many elements the
+	/// other patterns leave over, and binds them to the name as an array
+	Rest(Option<String>),
+	/// `obj { name age: a }`, matches objects that have every one of the keys, if their values match \
+	/// a key on its own binds the value to a name that's the same as the key
+	Object(Vec<(String, Pattern)>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// `pattern if guard => body`
+pub struct MatchArm {
+	pub pattern: Pattern,
+	/// the arm only gets picked if this is true (with the pattern's bindings available)
+	pub guard: Option<Expr>,
+	pub body: Expr,
+}
+
+/// displays the pattern the way it'd be written in the source code
+impl Display for Pattern {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Pattern::Wildcard => write!(f, "_"),
+			Pattern::Binding(name) => write!(f, "{name}"),
+			Pattern::Literal(lit) => write!(f, "{lit:#}"),
+			Pattern::Array(items) => {
+				write!(f, "[")?;
+				for (i, item) in items.iter().enumerate() {
+					if i != 0 {
+						write!(f, " ")?;
+					}
+					write!(f, "{item}")?;
+				}
+				write!(f, "]")
+			}
+			Pattern::Rest(name) => write!(f, "..{}", name.as_deref().unwrap_or_default()),
+			Pattern::Object(fields) => {
+				write!(f, "obj {{")?;
+				for (key, pattern) in fields {
+					match pattern {
+						Pattern::Binding(name) if name == key => write!(f, " {key}")?,
+						pattern => write!(f, " {key}: {pattern}")?,
+					}
+				}
+				write!(f, " }}")
+			}
+		}
+	}
+}
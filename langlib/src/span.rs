@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// a location in the source code \
+/// `start` and `end` are byte offsets, `line` and `col` point at `start` and are 1-based
+pub struct Span {
+	pub start: usize,
+	pub end: usize,
+	pub line: usize,
+	pub col: usize,
+}
+impl Span {
+	/// a span starting where `self` starts and ending where `end` ends
+	pub fn to(self, end: Span) -> Span {
+		Span {
+			end: end.end.max(self.end),
+			..self
+		}
+	}
+}
+impl Display for Span {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}", self.line, self.col)
+	}
+}